@@ -0,0 +1,16 @@
+func greeting(name: str): str {
+	return `Hello, ${name}!\n`;
+}
+
+func answer(): i32 {
+	return 42;
+}
+
+func main() {
+	let greeting = greeting("kitty");
+	printf(greeting);
+
+	printf(`answer == ${answer()} (0x${answer():04x})\n`);
+	printf(`${`[${greeting:.5}]`:10}\n`);
+	printf(`[${answer():-5}]\n`);
+}
@@ -0,0 +1,18 @@
+func wide(x: i64): i64 {
+	return x;
+}
+
+func big(): i64 {
+	return 5000000000;
+}
+
+func main() {
+	let a: i64 = 5;
+	let b = 7;
+	let c = wide(b);
+	let d = big();
+	printf(`${a} ${c} ${d} ${d:x}\n`);
+
+	let e = 3;
+	printf(`${e} ${to_str(e)}\n`);
+}
@@ -0,0 +1,22 @@
+func functional(letter: str): str {
+	return letter;
+}
+
+func returned(voidness: str) {
+	let let_ = voidness;
+	let _func = "_func";
+	let i32s = "i32s";
+	let strings = "strings";
+	let xreturn = "xreturn";
+
+	printf(let_);
+	printf(_func);
+	printf(i32s);
+	printf(strings);
+	printf(xreturn);
+}
+
+func main() {
+	let func_ = functional("letter");
+	returned(func_);
+}
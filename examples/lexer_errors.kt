@@ -0,0 +1,10 @@
+// NOTE: This is supposed to fail.
+
+func main() {
+	let number = 12abc;
+	let overflow = 99999999999999999999;
+	let escape = "\q";
+	let dyn_escape = `\z`;
+	let char = #;
+	printf("all of the above are reported at once");
+}
@@ -0,0 +1,46 @@
+func literal(): i32 {
+	return 1;
+}
+
+func var(): i32 {
+	let x = 2;
+	return x;
+}
+
+func arg(x: i32): i32 {
+	return x;
+}
+
+func call(): i32 {
+	return arg(3);
+}
+
+func nested(): i32 {
+	return arg(arg(var()));
+}
+
+func greeting(name: str): str {
+	let greeting = `hi ${name}`;
+	return greeting;
+}
+
+func side(name: str): i32 {
+	printf(`${name} `);
+	return 0;
+}
+
+func main() {
+	let a = literal();
+	let b = var();
+	let c = a;
+	let a = call();
+	let d = nested();
+	let e = greeting("kitty");
+	let f = e;
+
+	printf(`${a} ${b} ${c} ${d} ${f}\n`);
+
+	side("first");
+	let g = side("second");
+	printf(`${side("third")}${g}\n`);
+}
@@ -0,0 +1,6 @@
+func main(args: [str]): i32 {
+	let len = count(args);
+	printf(`${len} args, running ${at(args, 0)}\n`);
+
+	return 0;
+}
@@ -0,0 +1,9 @@
+func check(answer: i32) {
+	printf(`checking ${answer}\n`);
+	panic(`wrong answer: ${answer}`);
+}
+
+func main() {
+	check(41);
+	printf("unreachable\n");
+}
@@ -0,0 +1,10 @@
+func main() {
+	let kitty = concat("kit", "ty");
+	printf(`${kitty} has ${len(kitty)} bytes\n`);
+	printf(`${slice(kitty, 1, 4)} ${slice(kitty, 3, 100)}\n`);
+
+	printf(`${compare("kit", kitty)} ${compare(kitty, kitty)} ${compare(kitty, "kat")}\n`);
+
+	let answer = parse_int(" -42 lives");
+	printf(`${answer} ${concat("#", to_str(answer))}\n`);
+}
@@ -0,0 +1,12 @@
+func answer(): i32 {
+	return 42;
+	printf("never printed");
+}
+
+func no_answer(): i32 {
+	printf("falls off the end");
+}
+
+func main() {
+	printf(`${answer()}${no_answer()}\n`);
+}
@@ -0,0 +1,3 @@
+func main(args: str): str {
+	return args;
+}
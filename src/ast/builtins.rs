@@ -0,0 +1,91 @@
+use crate::lexer::Ident;
+use crate::lexer::Position;
+use crate::lexer::Ty;
+use crate::parser::decl::Arg;
+
+use super::func::Func;
+use super::tree::Tree;
+
+static MESSAGE_ARGS: [Arg<'static>; 1] = [arg("message", Ty::Str)];
+static STR_ARGS: [Arg<'static>; 1] = [arg("string", Ty::Str)];
+static STR_PAIR_ARGS: [Arg<'static>; 2] = [arg("left", Ty::Str), arg("right", Ty::Str)];
+static SLICE_ARGS: [Arg<'static>; 3] = [
+    arg("string", Ty::Str),
+    arg("start", Ty::I32),
+    arg("end", Ty::I32),
+];
+static INT_ARGS: [Arg<'static>; 1] = [arg("int", Ty::I32)];
+static LIST_ARGS: [Arg<'static>; 1] = [arg("list", Ty::StrList)];
+static INDEX_ARGS: [Arg<'static>; 2] = [arg("list", Ty::StrList), arg("index", Ty::I32)];
+
+static BUILTINS: [(&str, &[Arg<'static>], Ty, bool); 11] = [
+    ("printf", &STR_ARGS, Ty::Void, false),
+    ("abort", &MESSAGE_ARGS, Ty::Void, false),
+    ("panic", &MESSAGE_ARGS, Ty::Void, false),
+    ("len", &STR_ARGS, Ty::I32, false),
+    ("concat", &STR_PAIR_ARGS, Ty::Str, false),
+    ("compare", &STR_PAIR_ARGS, Ty::I32, false),
+    ("slice", &SLICE_ARGS, Ty::Str, false),
+    ("parse_int", &STR_ARGS, Ty::I32, false),
+    ("to_str", &INT_ARGS, Ty::Str, false),
+    ("count", &LIST_ARGS, Ty::I32, false),
+    ("at", &INDEX_ARGS, Ty::Str, false),
+];
+
+// calls to these never come back
+static DIVERGING: [&str; 2] = ["abort", "panic"];
+
+const fn arg(name: &'static str, ty: Ty) -> Arg<'static> {
+    Arg {
+        name: Ident::Ref(name),
+        pos: Position {
+            offset: 0,
+            line: 0,
+            col: 0,
+        },
+        ty,
+    }
+}
+
+impl<'t> Tree<'t> {
+    pub fn declare_builtins(&mut self) {
+        for (name, args, ret, variadic) in &BUILTINS {
+            let name = Ident::Ref(name);
+
+            self.funcs.insert(
+                name.clone(),
+                Func {
+                    name: name.clone(),
+                    args,
+                    ret: *ret,
+                    variadic: *variadic,
+                    start: None,
+                    pos: None,
+                    end: None,
+                },
+            );
+
+            for module in self.modules.values_mut() {
+                module.funcs.insert(name.clone());
+            }
+        }
+    }
+
+    pub(super) fn diverges(&self, name: &Ident) -> bool {
+        DIVERGING.contains(&name.inner())
+            && self.funcs.get(name).is_some_and(|func| func.pos.is_none())
+    }
+
+    #[cfg(feature = "llvm")]
+    pub fn remove_builtins(&mut self) {
+        for (name, ..) in &BUILTINS {
+            let name = Ident::Ref(name);
+
+            for module in self.modules.values_mut() {
+                module.funcs.remove(&name);
+            }
+
+            self.funcs.remove(&name);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::parser::expr::Expr;
+
+use super::func::Func;
+use super::stmt::Stmt;
+use super::tree::Tree;
+
+#[derive(Debug)]
+pub(super) struct Cfg {
+    blocks: Vec<Block>,
+}
+
+#[derive(Debug)]
+struct Block {
+    stmts: Vec<usize>,
+    exit: Exit,
+}
+
+// how control leaves a block
+#[derive(Debug, PartialEq)]
+enum Exit {
+    // into the next block, or off the end of the function for the last one
+    Next,
+    Return,
+    // through a call that never comes back, like `panic`
+    Diverge,
+}
+
+impl Cfg {
+    pub(super) fn build(func: &Func, tree: &Tree) -> Cfg {
+        let mut cfg = Cfg {
+            blocks: vec![Block::new()],
+        };
+
+        let mut next = func.start;
+        while let Some(next_) = next {
+            let stmt = tree.stmts[next_].as_ref().unwrap(); // FIXME
+            let block = cfg.blocks.len() - 1;
+            cfg.blocks[block].stmts.push(next_);
+
+            let exit = match stmt {
+                Stmt::Return { .. } => Exit::Return,
+                Stmt::Expr {
+                    expr: Expr::Func(func),
+                    ..
+                } if tree.diverges(&func.name) => Exit::Diverge,
+                _ => Exit::Next,
+            };
+
+            if exit != Exit::Next {
+                cfg.blocks[block].exit = exit;
+
+                // whatever follows has no way in
+                if stmt.next().is_some() {
+                    cfg.blocks.push(Block::new());
+                }
+            }
+
+            next = stmt.next();
+        }
+
+        cfg
+    }
+
+    // a block is reached from the entry or by falling out of a reached block
+    fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![true];
+        for (b, block) in self.blocks[..self.blocks.len() - 1].iter().enumerate() {
+            reachable.push(reachable[b] && block.exit == Exit::Next);
+        }
+
+        reachable
+    }
+
+    pub(super) fn falls_through(&self) -> bool {
+        let last = self.blocks.len() - 1;
+        self.reachable()[last] && self.blocks[last].exit == Exit::Next
+    }
+
+    pub(super) fn unreachable(&self) -> Vec<&[usize]> {
+        self.blocks
+            .iter()
+            .zip(self.reachable())
+            .filter(|(block, reachable)| !reachable && !block.stmts.is_empty())
+            .map(|(block, _)| block.stmts.as_slice())
+            .collect()
+    }
+}
+
+impl Block {
+    fn new() -> Block {
+        Block {
+            stmts: vec![],
+            exit: Exit::Next,
+        }
+    }
+}
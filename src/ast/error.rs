@@ -2,6 +2,8 @@ use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
+use crate::lexer::Ident;
+use crate::lexer::Position;
 use crate::lexer::Ty;
 
 use super::stmt::Stmt;
@@ -20,7 +22,35 @@ pub enum ErrorKind<'e> {
         returned: Ty,
         accepted: Vec<Ty>,
     },
-    MissingReturn(Ty),
+    MissingReturn {
+        func: Ident<'e>,
+        ret: Ty,
+        pos: Option<Position>,
+    },
+    UnknownVar {
+        stmt: &'e Stmt<'e>,
+        var: Ident<'e>,
+    },
+    UnknownFunc {
+        stmt: &'e Stmt<'e>,
+        func: Ident<'e>,
+    },
+    VoidLet {
+        stmt: &'e Stmt<'e>,
+        var: Ident<'e>,
+    },
+    UnreachableCode {
+        func: Ident<'e>,
+        start: Position,
+        end: Position,
+    },
+    MissingMain,
+    DuplicateMain {
+        pos: Position,
+    },
+    WrongMainSignature {
+        pos: Position,
+    },
     Multiple(Vec<Error<'e>>),
 }
 
@@ -35,9 +65,81 @@ impl<'e> Error<'e> {
         }
     }
 
-    pub(super) fn missing_ty(ty: Ty) -> Error<'e> {
+    pub(super) fn missing_return(func: Ident<'e>, ret: Ty, pos: Option<Position>) -> Error<'e> {
+        Error {
+            kind: ErrorKind::MissingReturn { func, ret, pos },
+        }
+    }
+
+    pub(super) fn unknown_var(stmt: &'e Stmt<'e>, var: Ident<'e>) -> Error<'e> {
         Error {
-            kind: ErrorKind::MissingReturn(ty),
+            kind: ErrorKind::UnknownVar { stmt, var },
+        }
+    }
+
+    pub(super) fn unknown_func(stmt: &'e Stmt<'e>, func: Ident<'e>) -> Error<'e> {
+        Error {
+            kind: ErrorKind::UnknownFunc { stmt, func },
+        }
+    }
+
+    pub(super) fn void_let(stmt: &'e Stmt<'e>, var: Ident<'e>) -> Error<'e> {
+        Error {
+            kind: ErrorKind::VoidLet { stmt, var },
+        }
+    }
+
+    pub(super) fn unreachable_code(func: Ident<'e>, start: Position, end: Position) -> Error<'e> {
+        Error {
+            kind: ErrorKind::UnreachableCode { func, start, end },
+        }
+    }
+
+    pub(super) fn missing_main() -> Error<'e> {
+        Error {
+            kind: ErrorKind::MissingMain,
+        }
+    }
+
+    pub(super) fn duplicate_main(pos: Position) -> Error<'e> {
+        Error {
+            kind: ErrorKind::DuplicateMain { pos },
+        }
+    }
+
+    pub(super) fn wrong_main_signature(pos: Position) -> Error<'e> {
+        Error {
+            kind: ErrorKind::WrongMainSignature { pos },
+        }
+    }
+
+    pub fn pos(&self) -> Option<Position> {
+        match &self.kind {
+            ErrorKind::WrongTy { stmt, .. } => stmt.pos(),
+            ErrorKind::MissingReturn { pos, .. } => *pos,
+            ErrorKind::UnknownVar { stmt, .. } => stmt.pos(),
+            ErrorKind::UnknownFunc { stmt, .. } => stmt.pos(),
+            ErrorKind::VoidLet { stmt, .. } => stmt.pos(),
+            ErrorKind::UnreachableCode { start, .. } => Some(*start),
+            ErrorKind::MissingMain => None,
+            ErrorKind::DuplicateMain { pos } => Some(*pos),
+            ErrorKind::WrongMainSignature { pos } => Some(*pos),
+            ErrorKind::Multiple(_) => None,
+        }
+    }
+
+    pub fn span(&self) -> Option<(Position, Position)> {
+        match &self.kind {
+            ErrorKind::UnreachableCode { start, end, .. } => Some((*start, *end)),
+            _ => None,
+        }
+    }
+
+    pub fn errors(&self) -> Vec<&Error<'e>> {
+        if let ErrorKind::Multiple(errors) = &self.kind {
+            errors.iter().flat_map(|error| error.errors()).collect()
+        } else {
+            vec![self]
         }
     }
 
@@ -86,7 +188,30 @@ impl<'e> Display for Error<'e> {
 
                 write!(fmt, "]")
             }
-            ErrorKind::MissingReturn(ty) => write!(fmt, "missing return({})", ty),
+            ErrorKind::MissingReturn { func, ret, .. } => {
+                write!(fmt, "missing return(func={}, ret={})", func.inner(), ret)
+            }
+            ErrorKind::UnknownVar { stmt, var } => {
+                write!(fmt, "unknown var(stmt={}, var={})", stmt, var.inner())
+            }
+            ErrorKind::UnknownFunc { stmt, func } => {
+                write!(fmt, "unknown func(stmt={}, func={})", stmt, func.inner())
+            }
+            ErrorKind::VoidLet { stmt, var } => {
+                write!(fmt, "void let(stmt={}, var={})", stmt, var.inner())
+            }
+            ErrorKind::UnreachableCode { func, start, .. } => write!(
+                fmt,
+                "unreachable code(func={}, line={})",
+                func.inner(),
+                start.line + 1
+            ),
+            ErrorKind::MissingMain => write!(fmt, "missing `main` func"),
+            ErrorKind::DuplicateMain { .. } => write!(fmt, "duplicate `main` func"),
+            ErrorKind::WrongMainSignature { .. } => write!(
+                fmt,
+                "wrong `main` signature(accepted=[ main(): void  main(): i32  main(args: [str]): void  main(args: [str]): i32 ])"
+            ),
             ErrorKind::Multiple(errors) => {
                 write!(fmt, "multiple errors: [")?;
 
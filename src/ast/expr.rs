@@ -2,6 +2,7 @@ use fnv::FnvHashMap;
 
 use crate::lexer::Ident;
 use crate::lexer::Ty;
+use crate::parser::expr::DynStringSeg;
 use crate::parser::expr::Expr;
 use crate::parser::expr::Literal;
 
@@ -15,16 +16,26 @@ impl<'e> Expr<'e> {
         stmt: &'e Stmt,
         vars: &FnvHashMap<Ident<'e>, Ty>,
         tree: &Tree,
-    ) -> Result<Ty> {
+    ) -> Result<'_, Ty> {
         match self {
             Expr::Literal(lit) => match lit {
-                Literal::Int { .. } => Ok(Ty::I32),
+                Literal::Int { id, int, .. } => {
+                    let ty = tree.lits.get(id).cloned().unwrap_or(Ty::I32);
+                    if ty == Ty::I32 && *int > i64::from(i32::MAX) {
+                        return Err(Error::wrong_ty(stmt, Ty::I64, vec![Ty::I32]));
+                    }
+
+                    Ok(ty)
+                }
                 Literal::String { .. } => Ok(Ty::Str),
-                Literal::RefDynString { .. } => Ok(Ty::Str),
-                Literal::OwnedDynString { .. } => Ok(Ty::Str),
+                Literal::RefDynString { segs, .. } => dyn_string_ty(segs, stmt, vars, tree),
+                Literal::OwnedDynString { segs, .. } => dyn_string_ty(segs, stmt, vars, tree),
             },
             Expr::Func(func) => {
-                let decl = tree.funcs.get(&func.name).unwrap(); // FIXME
+                let decl = match tree.funcs.get(&func.name) {
+                    Some(decl) => decl,
+                    None => return Err(Error::unknown_func(stmt, func.name.as_ref())),
+                };
 
                 if func.args.len() < decl.args.len() {
                     return Err(Error::wrong_ty(
@@ -59,7 +70,31 @@ impl<'e> Expr<'e> {
 
                 Ok(decl.ret)
             }
-            Expr::Var(var) => Ok(*vars.get(var).unwrap()), // FIXME
+            Expr::Var(var) => match vars.get(var) {
+                Some(ty) => Ok(*ty),
+                None => Err(Error::unknown_var(stmt, var.clone())),
+            },
         }
     }
 }
+
+fn dyn_string_ty<'s>(
+    segs: &'s [DynStringSeg<'s>],
+    stmt: &'s Stmt,
+    vars: &FnvHashMap<Ident<'s>, Ty>,
+    tree: &Tree,
+) -> Result<'s, Ty> {
+    for seg in segs {
+        if let DynStringSeg::Expr(expr, spec) = seg {
+            let ty = expr.ty(stmt, vars, tree)?;
+
+            if ty == Ty::Void || ty == Ty::StrList {
+                return Err(Error::wrong_ty(stmt, ty, vec![Ty::I32, Ty::Str]));
+            } else if !ty.is_int() && spec.is_some_and(|spec| spec.needs_int()) {
+                return Err(Error::wrong_ty(stmt, ty, vec![Ty::I32]));
+            }
+        }
+    }
+
+    Ok(Ty::Str)
+}
@@ -1,10 +1,12 @@
 use fnv::FnvHashMap;
 
 use crate::lexer::Ident;
+use crate::lexer::Position;
 use crate::lexer::Ty;
 use crate::parser::decl;
 use crate::parser::decl::Arg;
 
+use super::cfg::Cfg;
 use super::error::*;
 use super::stmt::Stmt;
 use super::tree::Tree;
@@ -16,6 +18,8 @@ pub struct Func<'f> {
     pub ret: Ty,
     pub variadic: bool,
     pub start: Option<usize>,
+    pub pos: Option<Position>,
+    pub end: Option<Position>,
 }
 
 impl<'f> Func<'f> {
@@ -27,6 +31,8 @@ impl<'f> Func<'f> {
             ret: func.ret,
             variadic: func.variadic,
             start: None,
+            pos: Some(func.pos),
+            end: Some(func.end),
         };
 
         for stmt in stmts {
@@ -43,11 +49,14 @@ impl<'f> Func<'f> {
         func
     }
 
-    pub(super) fn verify(&self, tree: &'f Tree) -> Result<()> {
+    pub(super) fn verify(&self, tree: &'f Tree) -> Result<'_, ()> {
+        if self.pos.is_none() {
+            return Ok(()); // builtins have no body to check
+        }
+
         let mut error = Error::multiple(vec![]);
 
         let mut vars = FnvHashMap::default();
-        let mut returned = false;
 
         for arg in self.args {
             vars.insert(arg.name.as_ref(), arg.ty);
@@ -59,9 +68,19 @@ impl<'f> Func<'f> {
             match stmt {
                 Stmt::Let { let_, next: next_ } => {
                     match let_.value.ty(stmt, &vars, tree) {
-                        Ok(ty) => {
-                            vars.insert(let_.name.as_ref(), ty);
+                        Ok(Ty::Void) => {
+                            error = error.concat(Error::void_let(stmt, let_.name.as_ref()));
+                            vars.insert(let_.name.as_ref(), let_.ty.unwrap_or(Ty::Void));
                         }
+                        Ok(ty) => match let_.ty {
+                            Some(ty_) if ty_ != ty => {
+                                error = error.concat(Error::wrong_ty(stmt, ty, vec![ty_]));
+                                vars.insert(let_.name.as_ref(), ty_);
+                            }
+                            _ => {
+                                vars.insert(let_.name.as_ref(), ty);
+                            }
+                        },
                         Err(err) => error = error.concat(err),
                     }
 
@@ -77,18 +96,32 @@ impl<'f> Func<'f> {
                         Err(err) => error = error.concat(err),
                     }
 
-                    returned = true;
                     next = *next_;
                 }
                 Stmt::Expr { expr, next: next_ } => {
-                    expr.ty(stmt, &vars, tree)?;
+                    if let Err(err) = expr.ty(stmt, &vars, tree) {
+                        error = error.concat(err);
+                    }
+
                     next = *next_;
                 }
             }
         }
 
-        if !returned && self.ret != Ty::Void {
-            return Err(Error::missing_ty(self.ret));
+        let cfg = Cfg::build(self, tree);
+        if cfg.falls_through() && self.ret != Ty::Void {
+            error = error.concat(Error::missing_return(self.name.clone(), self.ret, self.end));
+        }
+
+        for stmts in cfg.unreachable() {
+            let start = stmts
+                .iter()
+                .filter_map(|stmt| tree.stmts[*stmt].as_ref().and_then(Stmt::pos))
+                .next();
+
+            if let (Some(start), Some(end)) = (start, self.end) {
+                error = error.concat(Error::unreachable_code(self.name.clone(), start, end));
+            }
         }
 
         if let ErrorKind::Multiple(errors) = &mut error.kind {
@@ -104,3 +137,136 @@ impl<'f> Func<'f> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::lex;
+    use crate::parser::parse;
+
+    // the kind of each error, the part of its message before the details
+    fn errors(input: &str) -> Vec<String> {
+        let tokens = lex(input).unwrap();
+        let module = parse(&tokens).unwrap();
+        let mut tree = Tree::build(&[&module]);
+        tree.declare_builtins();
+        tree.infer();
+
+        match tree.verify() {
+            Ok(()) => vec![],
+            Err(err) => err
+                .errors()
+                .iter()
+                .map(|err| err.to_string().split('(').next().unwrap().to_string())
+                .collect(),
+        }
+    }
+
+    const DECLS: &str = "func number(): i32 { return 1; }\nfunc nothing() {}\nfunc main() {}\n";
+
+    fn func(ret: &str, body: &str) -> String {
+        format!("{}func f(a: i32){} {{ {} }}", DECLS, ret, body)
+    }
+
+    #[test]
+    fn lets_bind_lits_vars_and_calls() {
+        assert!(errors(&func("", "let b = 1;")).is_empty());
+        assert!(errors(&func("", "let b = a;")).is_empty());
+        assert!(errors(&func("", "let b = number();")).is_empty());
+        assert!(errors(&func("", "let b: i32 = number();")).is_empty());
+    }
+
+    #[test]
+    fn lets_reject_void_calls() {
+        assert_eq!(errors(&func("", "let b = nothing();")), vec!["void let"]);
+        assert_eq!(
+            errors(&func("", "let b: i32 = nothing();")),
+            vec!["void let"]
+        );
+    }
+
+    #[test]
+    fn lets_reject_values_of_another_ty() {
+        assert_eq!(errors(&func("", "let b: str = 1;")), vec!["wrong type"]);
+        assert_eq!(errors(&func("", "let b: str = a;")), vec!["wrong type"]);
+        assert_eq!(
+            errors(&func("", "let b: str = number();")),
+            vec!["wrong type"]
+        );
+    }
+
+    #[test]
+    fn returns_lits_vars_and_calls() {
+        assert!(errors(&func(": i32", "return 1;")).is_empty());
+        assert!(errors(&func(": i32", "return a;")).is_empty());
+        assert!(errors(&func(": i32", "return number();")).is_empty());
+        assert!(errors(&func("", "return nothing();")).is_empty());
+    }
+
+    #[test]
+    fn returns_reject_values_of_another_ty() {
+        assert_eq!(errors(&func(": str", "return 1;")), vec!["wrong type"]);
+        assert_eq!(errors(&func(": str", "return a;")), vec!["wrong type"]);
+        assert_eq!(
+            errors(&func(": i32", "return nothing();")),
+            vec!["wrong type"]
+        );
+        assert_eq!(errors(&func("", "return number();")), vec!["wrong type"]);
+    }
+
+    #[test]
+    fn reports_missing_returns_and_code_after_returns() {
+        assert!(errors(&func(": i32", "number(); return 1;")).is_empty());
+        assert_eq!(errors(&func(": i32", "number();")), vec!["missing return"]);
+        assert_eq!(
+            errors(&func(": i32", "return 1; number();")),
+            vec!["unreachable code"]
+        );
+        assert_eq!(
+            errors(&func("", "return nothing(); number(); return nothing();")),
+            vec!["unreachable code"]
+        );
+    }
+
+    #[test]
+    fn diverging_calls_end_their_block() {
+        assert!(errors(&func(": i32", r#"panic("x");"#)).is_empty());
+        assert!(errors(&func(": i32", r#"number(); abort("x");"#)).is_empty());
+        assert_eq!(
+            errors(&func(": i32", r#"panic("x"); return 1;"#)),
+            vec!["unreachable code"]
+        );
+        assert_eq!(
+            errors(&func("", r#"abort("x"); number();"#)),
+            vec!["unreachable code"]
+        );
+    }
+
+    #[test]
+    fn infers_each_lit_on_its_own() {
+        assert!(errors(&func(
+            "",
+            "let b: i64 = 1; let c: i32 = 1; let d = 3000000000; let e: i64 = d;"
+        ))
+        .is_empty());
+        assert_eq!(
+            errors(&func("", "let b: i32 = 3000000000;")),
+            vec!["wrong type"]
+        );
+    }
+
+    #[test]
+    fn defaults_lits_too_big_for_i32_to_i64() {
+        assert!(errors(&func("", "let b = 3000000000;")).is_empty());
+
+        let input = func("", "let b = 3000000000; let c = b; let d = 1;");
+        let tokens = lex(&input).unwrap();
+        let module = parse(&tokens).unwrap();
+        let mut tree = Tree::build(&[&module]);
+        tree.declare_builtins();
+        tree.infer();
+
+        let tys = tree.lits.values();
+        assert_eq!(tys.filter(|ty| **ty == Ty::I64).count(), 1);
+    }
+}
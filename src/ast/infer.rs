@@ -0,0 +1,184 @@
+use fnv::FnvHashMap;
+
+use crate::lexer::Ident;
+use crate::lexer::Ty;
+use crate::parser::expr::DynStringSeg;
+use crate::parser::expr::Expr;
+use crate::parser::expr::Literal;
+
+use super::func::Func;
+use super::stmt::Stmt;
+use super::tree::Tree;
+
+// int literals and untyped bindings get a type var each, uses of them add
+// constraints, whatever is left unconstrained at the end defaults to `i32`, or
+// to `i64` when one of its literals doesn't fit
+
+#[derive(Copy, Clone, Debug)]
+enum Term {
+    Var(usize),
+    Ty(Ty),
+}
+
+#[derive(Debug)]
+pub(super) struct Infer {
+    parents: Vec<usize>,
+    tys: Vec<Option<Ty>>,
+    lits: Vec<(usize, usize, i64)>,
+}
+
+impl Infer {
+    pub(super) fn run(tree: &Tree) -> FnvHashMap<usize, Ty> {
+        let mut infer = Infer {
+            parents: vec![],
+            tys: vec![],
+            lits: vec![],
+        };
+
+        for func in tree.funcs.values() {
+            infer.func(func, tree);
+        }
+
+        for (_, var, int) in &infer.lits {
+            let root = infer.find(*var);
+            if infer.tys[root].is_none() && *int > i64::from(i32::MAX) {
+                infer.tys[root] = Some(Ty::I64);
+            }
+        }
+
+        let mut lits = FnvHashMap::default();
+        for (id, var, _) in &infer.lits {
+            let ty = match infer.tys[infer.find(*var)] {
+                Some(ty) if ty.is_int() => ty,
+                _ => Ty::I32, // a clash is left for `verify` to report
+            };
+
+            lits.insert(*id, ty);
+        }
+
+        lits
+    }
+
+    fn func<'f>(&mut self, func: &'f Func<'f>, tree: &'f Tree) {
+        let mut vars = FnvHashMap::default();
+        for arg in func.args {
+            vars.insert(arg.name.as_ref(), Term::Ty(arg.ty));
+        }
+
+        let mut next = func.start;
+        while let Some(next_) = next {
+            let stmt = tree.stmts[next_].as_ref().unwrap(); // FIXME
+            match stmt {
+                Stmt::Let { let_, .. } => {
+                    let term = self.expr(&let_.value, &vars, tree);
+                    let term = match let_.ty {
+                        Some(ty) => {
+                            self.unify(term, Term::Ty(ty));
+                            Term::Ty(ty)
+                        }
+                        None => term,
+                    };
+
+                    vars.insert(let_.name.as_ref(), term);
+                }
+                Stmt::Return { ret, .. } => {
+                    let term = self.expr(&ret.0, &vars, tree);
+                    self.unify(term, Term::Ty(func.ret));
+                }
+                Stmt::Expr { expr, .. } => {
+                    self.expr(expr, &vars, tree);
+                }
+            }
+
+            next = stmt.next();
+        }
+    }
+
+    fn expr<'e>(
+        &mut self,
+        expr: &'e Expr<'e>,
+        vars: &FnvHashMap<Ident<'e>, Term>,
+        tree: &Tree,
+    ) -> Term {
+        match expr {
+            Expr::Literal(Literal::Int { id, int, .. }) => {
+                let var = self.var();
+                self.lits.push((*id, var, *int));
+                Term::Var(var)
+            }
+            Expr::Literal(Literal::String { .. }) => Term::Ty(Ty::Str),
+            Expr::Literal(Literal::RefDynString { segs, .. }) => self.dyn_string(segs, vars, tree),
+            Expr::Literal(Literal::OwnedDynString { segs, .. }) => {
+                self.dyn_string(segs, vars, tree)
+            }
+            Expr::Func(func) => {
+                let decl = tree.funcs.get(&func.name);
+
+                for (a, arg) in func.args.inner().iter().enumerate() {
+                    let term = self.expr(arg, vars, tree);
+                    if let Some(arg) = decl.and_then(|decl| decl.args.get(a)) {
+                        self.unify(term, Term::Ty(arg.ty));
+                    }
+                }
+
+                match decl {
+                    Some(decl) => Term::Ty(decl.ret),
+                    None => Term::Var(self.var()),
+                }
+            }
+            Expr::Var(var) => match vars.get(var) {
+                Some(term) => *term,
+                None => Term::Var(self.var()),
+            },
+        }
+    }
+
+    fn dyn_string<'s>(
+        &mut self,
+        segs: &'s [DynStringSeg<'s>],
+        vars: &FnvHashMap<Ident<'s>, Term>,
+        tree: &Tree,
+    ) -> Term {
+        for seg in segs {
+            if let DynStringSeg::Expr(expr, _) = seg {
+                self.expr(expr, vars, tree);
+            }
+        }
+
+        Term::Ty(Ty::Str)
+    }
+
+    fn var(&mut self) -> usize {
+        self.parents.push(self.parents.len());
+        self.tys.push(None);
+        self.parents.len() - 1
+    }
+
+    fn find(&self, mut var: usize) -> usize {
+        while self.parents[var] != var {
+            var = self.parents[var];
+        }
+
+        var
+    }
+
+    // clashing types are kept as they are, `verify` reports them with the stmt
+    fn unify(&mut self, left: Term, right: Term) {
+        match (left, right) {
+            (Term::Var(left), Term::Var(right)) => {
+                let (left, right) = (self.find(left), self.find(right));
+                if left != right {
+                    self.parents[right] = left;
+                    self.tys[left] = self.tys[left].or(self.tys[right]);
+                }
+            }
+            (Term::Var(var), Term::Ty(ty)) | (Term::Ty(ty), Term::Var(var)) => {
+                let root = self.find(var);
+                if self.tys[root].is_none() {
+                    self.tys[root] = Some(ty);
+                }
+            }
+            (Term::Ty(_), Term::Ty(_)) => (),
+        }
+    }
+}
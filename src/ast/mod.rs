@@ -2,9 +2,12 @@ use fnv::FnvHashMap;
 
 use crate::parser;
 
+mod builtins;
+mod cfg;
 mod error;
 mod expr;
 mod func;
+mod infer;
 mod module;
 mod stmt;
 mod tree;
@@ -15,12 +18,15 @@ pub use module::Module;
 pub use stmt::Stmt;
 pub use tree::Tree;
 
+use infer::Infer;
+
 impl<'t> Tree<'t> {
     pub fn build(modules: &[&'t parser::Module<'t>]) -> Tree<'t> {
         let mut tree = Tree {
             modules: FnvHashMap::default(),
             funcs: FnvHashMap::default(),
             stmts: vec![],
+            lits: FnvHashMap::default(),
         };
 
         for module in modules {
@@ -32,7 +38,11 @@ impl<'t> Tree<'t> {
         tree
     }
 
-    pub fn verify(&self) -> Result<()> {
+    pub fn infer(&mut self) {
+        self.lits = Infer::run(self);
+    }
+
+    pub fn verify(&self) -> Result<'_, ()> {
         let mut error = Error::multiple(vec![]);
 
         for module in self.modules.values() {
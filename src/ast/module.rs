@@ -1,6 +1,8 @@
 use fnv::FnvHashSet;
 
 use crate::lexer::Ident;
+use crate::lexer::Position;
+use crate::lexer::Ty;
 use crate::parser;
 
 use super::error::*;
@@ -11,6 +13,7 @@ use super::tree::Tree;
 pub struct Module<'m> {
     pub name: Ident<'m>,
     pub funcs: FnvHashSet<Ident<'m>>,
+    pub mains: Vec<Position>,
 }
 
 impl<'m> Module<'m> {
@@ -19,10 +22,15 @@ impl<'m> Module<'m> {
         let mut module = Module {
             name: module.name.as_ref(),
             funcs: FnvHashSet::default(),
+            mains: vec![],
         };
 
         for func in funcs {
             let name = func.name.as_ref();
+            if name.inner() == "main" {
+                module.mains.push(func.pos);
+            }
+
             let func = Func::build(func, tree);
             module.funcs.insert(name.clone());
             tree.funcs.insert(name.clone(), func);
@@ -31,9 +39,16 @@ impl<'m> Module<'m> {
         module
     }
 
-    pub(super) fn verify(&self, tree: &'m Tree) -> Result<()> {
+    pub(super) fn verify(&self, tree: &'m Tree) -> Result<'_, ()> {
         let mut error = Error::multiple(vec![]);
 
+        // only the root module is run, the repl builds its own
+        if self.name.inner() == "main" {
+            if let Err(err) = self.verify_main(tree) {
+                error = error.concat(err);
+            }
+        }
+
         for func in &self.funcs {
             let func = tree.funcs.get(func).unwrap();
             if let Err(err) = func.verify(tree) {
@@ -53,4 +68,25 @@ impl<'m> Module<'m> {
             panic!(); // FIXME
         }
     }
+
+    fn verify_main(&self, tree: &'m Tree) -> Result<'_, ()> {
+        if self.mains.is_empty() {
+            return Err(Error::missing_main());
+        } else if self.mains.len() > 1 {
+            return Err(Error::duplicate_main(self.mains[1]));
+        }
+
+        let main = tree.funcs.get(&Ident::Ref("main")).unwrap(); // FIXME
+        let args = match main.args {
+            [] => true,
+            [arg] => arg.ty == Ty::StrList,
+            _ => false,
+        };
+
+        if !args || (main.ret != Ty::Void && main.ret != Ty::I32) {
+            return Err(Error::wrong_main_signature(self.mains[0]));
+        }
+
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@ use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
+use crate::lexer::Position;
 use crate::parser::expr::Expr;
 use crate::parser::stmt;
 use crate::parser::stmt::Let;
@@ -34,6 +35,18 @@ impl<'s> Stmt<'s> {
         }
     }
 
+    pub fn pos(&self) -> Option<Position> {
+        match self {
+            Stmt::Let { let_, .. } => Some(let_.pos),
+            Stmt::Return { ret, .. } => Some(ret.1),
+            Stmt::Expr {
+                expr: Expr::Func(func),
+                ..
+            } => Some(func.pos),
+            Stmt::Expr { .. } => None,
+        }
+    }
+
     pub(super) fn next_mut(&mut self) -> &mut Option<usize> {
         match self {
             Stmt::Let { next, .. } => next,
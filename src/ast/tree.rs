@@ -5,6 +5,7 @@ use std::fmt::Formatter;
 use fnv::FnvHashMap;
 
 use crate::lexer::Ident;
+use crate::lexer::Ty;
 
 use super::func::Func;
 use super::module::Module;
@@ -15,6 +16,7 @@ pub struct Tree<'t> {
     pub modules: FnvHashMap<Ident<'t>, Module<'t>>,
     pub funcs: FnvHashMap<Ident<'t>, Func<'t>>,
     pub stmts: Vec<Option<Stmt<'t>>>,
+    pub lits: FnvHashMap<usize, Ty>, // the tys of int literals by id
 }
 
 impl<'t> Display for Tree<'t> {
@@ -1,9 +1,119 @@
 use std::path::PathBuf;
 
+use structopt::clap::AppSettings;
 use structopt::StructOpt;
 
+#[cfg(feature = "llvm")]
+use crate::compiler::Emit;
+#[cfg(feature = "llvm")]
+use crate::compiler::OptLevel;
+
+// `shedder <file>` is kept as a short form of `shedder compile <file>`
 #[derive(StructOpt, Debug)]
+#[structopt(
+    name = "shedder",
+    raw(settings = "&[AppSettings::ArgRequiredElseHelp, AppSettings::ArgsNegateSubcommands]")
+)]
 pub struct Opt {
+    #[structopt(long = "tab-width", default_value = "4")]
+    pub tab_width: usize,
+
     #[structopt(parse(from_os_str))]
-    pub file: PathBuf,
+    pub file: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    pub cmd: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    #[cfg(feature = "llvm")]
+    #[structopt(name = "compile")]
+    Compile {
+        #[structopt(long = "tab-width", default_value = "4")]
+        tab_width: usize,
+
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    #[cfg(feature = "llvm")]
+    #[structopt(name = "build")]
+    Build {
+        #[structopt(long = "tab-width", default_value = "4")]
+        tab_width: usize,
+
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+
+        #[structopt(flatten)]
+        opts: BuildOpts,
+    },
+    #[structopt(name = "run")]
+    Run {
+        #[structopt(long = "interpret")]
+        interpret: bool,
+
+        #[structopt(long = "target")]
+        target: Option<String>,
+
+        #[structopt(long = "tab-width", default_value = "4")]
+        tab_width: usize,
+
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+
+        args: Vec<String>,
+    },
+    #[structopt(name = "fmt")]
+    Fmt {
+        #[structopt(long = "check")]
+        check: bool,
+
+        #[structopt(parse(from_os_str))]
+        files: Vec<PathBuf>,
+    },
+    #[structopt(name = "lsp")]
+    Lsp,
+    #[structopt(name = "repl")]
+    Repl,
+}
+
+#[cfg(feature = "llvm")]
+#[derive(StructOpt, Debug)]
+pub struct BuildOpts {
+    #[structopt(short = "o", parse(from_os_str))]
+    pub output: Option<PathBuf>,
+
+    #[structopt(long = "linker")]
+    pub linker: Option<String>,
+
+    #[structopt(long = "keep-intermediates")]
+    pub keep_intermediates: bool,
+
+    #[structopt(long = "emit", raw(number_of_values = "1", use_delimiter = "true"))]
+    pub emit: Vec<Emit>,
+
+    #[structopt(short = "O", default_value = "0")]
+    pub opt: OptLevel,
+
+    #[structopt(short = "g")]
+    pub debug: bool,
+
+    #[structopt(long = "print-passes")]
+    pub print_passes: bool,
+
+    #[structopt(long = "target")]
+    pub target: Option<String>,
+
+    #[structopt(long = "target-cpu")]
+    pub target_cpu: Option<String>,
+
+    #[structopt(
+        long = "target-feature",
+        raw(number_of_values = "1", use_delimiter = "true")
+    )]
+    pub target_features: Vec<String>,
+
+    #[structopt(long = "wasm-import-module")]
+    pub wasm_import_module: Option<String>,
 }
@@ -9,25 +9,29 @@ use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::module::Linkage;
 use inkwell::module::Module;
-use inkwell::targets::CodeModel;
 use inkwell::targets::FileType;
-use inkwell::targets::RelocMode;
-use inkwell::targets::Target;
 use inkwell::targets::TargetMachine;
 use inkwell::types::BasicTypeEnum;
 use inkwell::types::FunctionType;
+use inkwell::types::PointerType;
+use inkwell::types::StructType;
 use inkwell::values::BasicValue;
 use inkwell::values::BasicValueEnum;
 use inkwell::values::FunctionValue;
-use inkwell::values::PointerValue;
+use inkwell::values::IntValue;
 use inkwell::AddressSpace;
-use inkwell::OptimizationLevel;
 
+use crate::lexer::FormatSpec;
 use crate::lexer::Ident;
+use crate::lexer::Position;
 use crate::lexer::Ty;
 use crate::parser::decl::Arg;
 
+use super::debug::DebugInfo;
+use super::emit::Emit;
 use super::error::*;
+use super::runtime;
+use super::runtime::RuntimeFn;
 
 #[derive(Debug)]
 pub(super) struct Compiler<'c> {
@@ -35,8 +39,11 @@ pub(super) struct Compiler<'c> {
     builder: Builder,
     module: Option<Module>,
     modules: Vec<Module>,
-    // TODO: passes
     funcs: FnvHashMap<Ident<'c>, Func<'c>>,
+    runtime: Module,
+    lits: &'c FnvHashMap<usize, Ty>,
+    pub(super) file: String,
+    debug: Option<DebugInfo<'c>>,
 }
 
 #[derive(Debug)]
@@ -48,7 +55,6 @@ pub(super) struct State<'s> {
 #[derive(Debug)]
 struct Func<'f> {
     func: FunctionValue,
-    ty: CompilerTy<'f>,
     args: FnvHashMap<Ident<'f>, BasicValueEnum>,
     blocks: FnvHashMap<Ident<'f>, Block<'f>>,
 }
@@ -64,29 +70,26 @@ enum Var<'v> {
     Alias(Ident<'v>),
     Arg,
     Var(BasicValueEnum),
-    Group(Vec<Ident<'v>>),
-    Global(PointerValue),
-}
-
-#[derive(Clone, Debug)]
-pub(super) enum CompilerTy<'t> {
-    Ty(Ty),
-    FunctionType {
-        args: &'t [CompilerTy<'t>],
-        ret: &'t CompilerTy<'t>,
-    },
 }
 
 #[derive(Debug)]
 pub struct Compiled {
-    module: Module,
+    pub(super) module: Module,
+    pub(super) runtime: Module,
     tm: Option<TargetMachine>,
 }
 
 impl<'c> Compiler<'c> {
-    pub(super) fn new() -> Compiler<'c> {
+    pub(super) fn new(
+        file: &Path,
+        lits: &'c FnvHashMap<usize, Ty>,
+        debug: bool,
+        ptr_bits: u64,
+        hosted: bool,
+    ) -> Compiler<'c> {
         let ctx = Context::create();
         let builder = ctx.create_builder();
+        let runtime = runtime::load(&ctx, ptr_bits, hosted);
 
         Compiler {
             ctx,
@@ -94,6 +97,14 @@ impl<'c> Compiler<'c> {
             module: None,
             modules: vec![],
             funcs: FnvHashMap::default(),
+            runtime,
+            lits,
+            file: file.display().to_string(),
+            debug: if debug {
+                Some(DebugInfo::new(file, ptr_bits))
+            } else {
+                None
+            },
         }
     }
 
@@ -102,6 +113,10 @@ impl<'c> Compiler<'c> {
     }
 
     pub(super) fn new_module(&mut self, module: &str) {
+        if let Some(debug) = &mut self.debug {
+            debug.finish_module();
+        }
+
         if let Some(module) = self.module.take() {
             self.modules.push(module);
         }
@@ -113,17 +128,18 @@ impl<'c> Compiler<'c> {
         &mut self,
         name: Ident<'c>,
         args: &[Arg<'c>],
-        ty: CompilerTy<'c>,
+        ret: Ty,
         variadic: bool,
+        pos: Option<Position>,
     ) {
         let mut args_ = vec![];
         for arg in args {
-            args_.push(CompilerTy::from(arg.ty).as_basic_type(&self.ctx));
+            args_.push(basic_type(&self.ctx, arg.ty));
         }
 
         let func = self.module().add_function(
-            name.inner(),
-            ty.as_fn_type(&self.ctx, &args_, variadic),
+            symbol(name.inner()),
+            fn_type(&self.ctx, ret, &args_, variadic),
             None,
         );
 
@@ -132,38 +148,58 @@ impl<'c> Compiler<'c> {
             args_.insert(arg.name.clone(), func.get_nth_param(a as u32).unwrap()); // FIXME
         }
 
+        if let (Some(debug), Some(pos)) = (&mut self.debug, pos) {
+            debug.func(name.clone(), func, pos);
+        }
+
         self.funcs.insert(
             name,
             Func {
                 func,
-                ty,
                 args: args_,
                 blocks: FnvHashMap::default(),
             },
         );
     }
 
-    pub(super) fn add_external_function(
-        &mut self,
-        name: Ident<'c>,
-        ty: CompilerTy<'c>,
-        variadic: bool,
-    ) {
-        let func = self.module().add_function(
-            name.inner(),
-            ty.as_fn_type(&self.ctx, &[], variadic),
-            Some(Linkage::AvailableExternally),
-        );
+    pub(super) fn add_entry(&self, main: &Ident<'c>) {
+        let argv = c_str_type(&self.ctx).ptr_type(AddressSpace::Generic);
+        let ty = self
+            .ctx
+            .i32_type()
+            .fn_type(&[self.ctx.i32_type().into(), argv.into()], false);
 
-        self.funcs.insert(
-            name,
-            Func {
-                func,
-                ty,
-                args: FnvHashMap::default(), // FIXME
-                blocks: FnvHashMap::default(),
-            },
-        );
+        let entry = self.module().add_function("main", ty, None);
+        let args = self.runtime_function(RuntimeFn::Args);
+        self.builder
+            .position_at_end(&entry.append_basic_block("entry"));
+
+        let main = self.funcs[main].func;
+        let args = if main.count_params() == 0 {
+            vec![]
+        } else {
+            let argc = entry.get_nth_param(0).unwrap(); // FIXME
+            let argv = entry.get_nth_param(1).unwrap(); // FIXME
+
+            vec![self
+                .builder
+                .build_call(args, &[argc, argv], "args")
+                .try_as_basic_value()
+                .left()
+                .unwrap()] // FIXME
+        };
+
+        let code = match self
+            .builder
+            .build_call(main, &args, "code")
+            .try_as_basic_value()
+            .left()
+        {
+            Some(code) => code,
+            None => self.ctx.i32_type().const_int(0, false).into(),
+        };
+
+        self.builder.build_return(Some(&code));
     }
 
     pub(super) fn append_block(&mut self, func: &Ident<'c>, name: Ident<'c>) {
@@ -194,40 +230,181 @@ impl<'c> Compiler<'c> {
             .insert(name, Var::Var(value));
     }
 
-    pub(super) fn register_var_group(
+    // joins the parts of a dyn str, the ones flagged as owned were made only for
+    // it and are freed once copied in
+    pub(super) fn format_string(
         &mut self,
         state: &State<'c>,
         name: Ident<'c>,
-        group: Vec<Ident<'c>>,
+        parts: &[(BasicValueEnum, bool)],
     ) {
-        self.funcs
-            .get_mut(&state.func)
+        let mut string = None;
+        for &(part, owned) in parts {
+            string = Some(match string {
+                None => (part, owned),
+                Some((string, owned_)) => {
+                    let joined = self
+                        .call_runtime(state, RuntimeFn::StrConcat, &[string, part])
+                        .unwrap(); // FIXME
+                    if owned_ {
+                        self.free_str(state, string);
+                    }
+
+                    if owned {
+                        self.free_str(state, part);
+                    }
+
+                    (joined, true)
+                }
+            });
+        }
+
+        // the lexer gives every dyn string at least one seg, and the builtins free
+        // the dyn strs passed to them, so each needs a copy of its own
+        let var = match string.unwrap() {
+            (string, true) => string,
+            (string, false) => self.format_str(state, string, &FormatSpec::default()),
+        };
+        self.register_var(state, name, var);
+    }
+
+    // hex and octal print the bits, so only decimal ints are sign extended
+    pub(super) fn format_int(
+        &self,
+        state: &State,
+        int: IntValue,
+        spec: &FormatSpec,
+    ) -> BasicValueEnum {
+        let base = match spec.conv {
+            Some('x') | Some('X') => 16,
+            Some('o') => 8,
+            _ => 10,
+        };
+
+        self.builder
+            .position_at_end(&self.funcs[&state.func].blocks[&state.block].block);
+
+        let i64_type = self.ctx.i64_type();
+        let int = if int.get_type().get_bit_width() == 64 {
+            int
+        } else if base == 10 {
+            self.builder.build_int_s_extend(int, i64_type, "int")
+        } else {
+            self.builder.build_int_z_extend(int, i64_type, "int")
+        };
+
+        let args = [
+            int.into(),
+            self.ctx.i32_type().const_int(base, false).into(),
+            self.const_bool(spec.conv == Some('X')),
+            self.const_bool(spec.left),
+            self.const_bool(spec.zero),
+            self.const_width(spec.width),
+            self.const_width(spec.precision),
+        ];
+
+        self.call_runtime(state, RuntimeFn::FormatInt, &args)
             .unwrap() // FIXME
-            .blocks
-            .get_mut(&state.block)
+    }
+
+    pub(super) fn format_str(
+        &self,
+        state: &State,
+        str: BasicValueEnum,
+        spec: &FormatSpec,
+    ) -> BasicValueEnum {
+        let args = [
+            str,
+            self.const_bool(spec.left),
+            self.const_width(spec.width),
+            self.const_width(spec.precision),
+        ];
+
+        self.call_runtime(state, RuntimeFn::FormatStr, &args)
             .unwrap() // FIXME
-            .vars
-            .insert(name, Var::Group(group));
     }
 
-    pub(super) fn add_global_string(&mut self, state: &State<'c>, name: Ident<'c>, string: &str) {
-        let block = self
-            .funcs
-            .get_mut(&state.func)
+    fn const_bool(&self, value: bool) -> BasicValueEnum {
+        self.ctx
+            .bool_type()
+            .const_int(u64::from(value), false)
+            .into()
+    }
+
+    // the runtime takes a negative width or precision as none
+    fn const_width(&self, width: Option<usize>) -> BasicValueEnum {
+        let width = width.map_or(-1, |width| width as i64);
+        self.ctx.i32_type().const_int(width as u64, true).into()
+    }
+
+    fn runtime_function(&self, func: RuntimeFn) -> FunctionValue {
+        if let Some(func) = self.module().get_function(func.name()) {
+            return func;
+        }
+
+        let ty = self
+            .runtime
+            .get_function(func.name())
             .unwrap() // FIXME
-            .blocks
-            .get_mut(&state.block)
-            .unwrap(); // FIXME
-        self.builder.position_at_end(&block.block);
+            .get_type();
 
-        let var = self
-            .builder
-            .build_global_string_ptr(string, name.inner())
-            .as_pointer_value();
-        block.vars.insert(name, Var::Global(var));
+        self.module().add_function(func.name(), ty, None)
+    }
+
+    pub(super) fn call_runtime(
+        &self,
+        state: &State,
+        func: RuntimeFn,
+        args: &[BasicValueEnum],
+    ) -> Option<BasicValueEnum> {
+        let name = func.name();
+        let func = self.runtime_function(func);
+        self.builder
+            .position_at_end(&self.funcs[&state.func].blocks[&state.block].block);
+
+        self.builder
+            .build_call(func, args, name)
+            .try_as_basic_value()
+            .left()
+    }
+
+    pub(super) fn is_str(&self, value: BasicValueEnum) -> bool {
+        match value {
+            BasicValueEnum::StructValue(value) => value.get_type() == str_type(&self.ctx),
+            _ => false,
+        }
+    }
+
+    pub(super) fn add_global_string(&mut self, state: &State<'c>, name: Ident<'c>, string: &str) {
+        let var = self.const_str(name.inner(), string);
+        self.register_var(state, name, var);
+    }
+
+    // a global of the bytes rather than a C string, a `\0` escape puts a NUL inside
+    pub(super) fn const_str(&self, name: &str, string: &str) -> BasicValueEnum {
+        let i8_type = self.ctx.i8_type();
+        let bytes = string
+            .bytes()
+            .chain(Some(0)) // runtime strings are NUL terminated too
+            .map(|byte| i8_type.const_int(u64::from(byte), false))
+            .collect::<Vec<_>>();
+        let bytes = i8_type.const_array(&bytes);
+
+        let global = self.module().add_global(bytes.get_type(), None, name);
+        global.set_initializer(&bytes);
+        global.set_constant(true);
+        global.set_linkage(Linkage::Private);
+
+        let zero = self.ctx.i32_type().const_int(0, false);
+        let ptr = unsafe { global.as_pointer_value().const_in_bounds_gep(&[zero, zero]) };
+        let len = self.ctx.i32_type().const_int(string.len() as u64, false);
+
+        self.ctx
+            .const_struct(&[ptr.into(), len.into()], false)
+            .into()
     }
 
-    pub(super) fn const_int(&mut self, state: &State<'c>, name: Ident<'c>, value: u64) {
+    pub(super) fn const_int(&mut self, state: &State<'c>, name: Ident<'c>, id: usize, value: u64) {
         let block = self
             .funcs
             .get_mut(&state.func)
@@ -237,10 +414,12 @@ impl<'c> Compiler<'c> {
             .unwrap(); // FIXME
         self.builder.position_at_end(&block.block);
 
-        let var = self
-            .ctx
-            .i32_type() // FIXME: custom size
-            .const_int(value, false); // FIXME: custom `sign_extend` value
+        let ty = match self.lits.get(&id) {
+            Some(Ty::I64) => self.ctx.i64_type(),
+            _ => self.ctx.i32_type(),
+        };
+
+        let var = ty.const_int(value, false); // FIXME: custom `sign_extend` value
         block.vars.insert(name, Var::Var(var.into()));
     }
 
@@ -261,23 +440,20 @@ impl<'c> Compiler<'c> {
             Var::Alias(var) => self.get_var(state, var),
             Var::Arg => func.args.get(name).map(|arg| vec![*arg]),
             Var::Var(var) => Some(vec![*var]),
-            Var::Group(vars_) => {
-                let mut vars = vec![];
-                for var in vars_ {
-                    vars.append(&mut self.get_var(state, var).unwrap());
-                }
-
-                Some(vars)
-            }
-            Var::Global(var) => Some(vec![(*var).into()]),
         }
     }
 
+    pub(super) fn var(&self, state: &State, name: &Ident) -> Result<Vec<BasicValueEnum>> {
+        self.get_var(state, name)
+            .ok_or_else(|| Error::unknown_var(state.func.inner().into(), name.inner().into()))
+    }
+
     pub(super) fn call(
         &self,
         state: &State,
         func: &Ident,
         args: &[BasicValueEnum],
+        pos: Position,
     ) -> Option<BasicValueEnum> {
         let block = self
             .funcs
@@ -289,7 +465,10 @@ impl<'c> Compiler<'c> {
         self.builder.position_at_end(&block.block);
 
         let name = func;
-        let func = self.funcs.get(name).unwrap(); // FIXME
+        let func = match self.funcs.get(name) {
+            Some(func) => func,
+            None => return self.call_builtin(state, name, args, pos),
+        };
 
         let call = self.builder.build_call(
             func.func,
@@ -313,7 +492,61 @@ impl<'c> Compiler<'c> {
         self.builder.build_return(value);
     }
 
-    pub(super) fn compiled(self) -> Compiled {
+    pub(super) fn unreachable(&self, state: &State) {
+        self.builder
+            .position_at_end(&self.funcs[&state.func].blocks[&state.block].block);
+        self.builder.build_unreachable();
+    }
+
+    pub(super) fn terminated(&self, state: &State) -> bool {
+        self.funcs[&state.func].blocks[&state.block]
+            .block
+            .get_terminator()
+            .is_some()
+    }
+
+    pub(super) fn locate(&self, state: &State, pos: Option<Position>) -> Result<()> {
+        if let (Some(debug), Some(pos)) = (&self.debug, pos) {
+            let block = &self.funcs[&state.func].blocks[&state.block].block;
+            debug.locate(&state.func, block, pos)?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn declare_var(
+        &self,
+        state: &State,
+        name: &Ident,
+        arg: Option<u32>,
+        pos: Position,
+    ) -> Result<()> {
+        let debug = match &self.debug {
+            Some(debug) => debug,
+            None => return Ok(()),
+        };
+
+        if let Some(vars) = self.get_var(state, name) {
+            if let BasicValueEnum::StructValue(_) = vars[0] {
+                if !self.is_str(vars[0]) {
+                    return Ok(()); // FIXME: describe `[str]` too
+                }
+            }
+
+            if vars.len() == 1 {
+                let block = &self.funcs[&state.func].blocks[&state.block].block;
+                debug.var(&state.func, block, name, arg, vars[0], pos)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn compiled(mut self) -> Result<Compiled> {
+        if let Some(debug) = &mut self.debug {
+            debug.finish_module();
+        }
+
         let module = self.ctx.create_module("main");
 
         if let Some(module_) = self.module {
@@ -324,104 +557,131 @@ impl<'c> Compiler<'c> {
             module.link_in_module(module_).unwrap(); // FIXME
         }
 
-        Compiled { module, tm: None }
+        // debug info is only complete after `finish_module`, so nothing can be verified earlier
+        let mut funcs = self
+            .funcs
+            .keys()
+            .map(|name| name.inner())
+            .collect::<Vec<_>>();
+        funcs.sort();
+
+        for name in funcs {
+            let func = module.get_function(symbol(name)).unwrap(); // FIXME
+            if !func.verify(false) {
+                // the function verifier only prints, the module one gives the reason back
+                let reason = match module.verify() {
+                    Ok(()) => String::new(),
+                    Err(err) => err.to_string(),
+                };
+
+                return Err(Error::invalid_function(name.into(), reason));
+            }
+        }
+
+        module.verify().map_err(Error::invalid_module)?;
+
+        Ok(Compiled {
+            module,
+            runtime: self.runtime,
+            tm: None,
+        })
     }
 }
 
-impl<'t> CompilerTy<'t> {
-    pub(super) fn fn_type(&'t self, args: &'t [CompilerTy<'t>]) -> CompilerTy<'t> {
-        CompilerTy::FunctionType { args, ret: &self }
+fn basic_type(ctx: &Context, ty: Ty) -> BasicTypeEnum {
+    match ty {
+        Ty::I32 => ctx.i32_type().into(),
+        Ty::I64 => ctx.i64_type().into(),
+        Ty::Str => str_type(ctx).into(),
+        Ty::StrList => str_list_type(ctx).into(),
+        Ty::Void => panic!(), // FIXME
     }
+}
 
-    fn as_basic_type(&self, ctx: &Context) -> BasicTypeEnum {
-        match self {
-            CompilerTy::Ty(ty) => match ty {
-                Ty::I32 => ctx.i32_type().into(),
-                Ty::Str => {
-                    ctx.i8_type()
-                        .ptr_type(AddressSpace::Generic) // TODO: choose address space
-                        .into()
-                }
-                Ty::Void => panic!(), // FIXME
-            },
-            CompilerTy::FunctionType { .. } => panic!(), // FIXME
-        }
+fn fn_type(ctx: &Context, ret: Ty, args: &[BasicTypeEnum], variadic: bool) -> FunctionType {
+    match ret {
+        Ty::I32 => ctx.i32_type().fn_type(args, variadic),
+        Ty::I64 => ctx.i64_type().fn_type(args, variadic),
+        Ty::Str => str_type(ctx).fn_type(args, variadic),
+        Ty::StrList => str_list_type(ctx).fn_type(args, variadic),
+        Ty::Void => ctx.void_type().fn_type(args, variadic),
     }
+}
 
-    fn as_fn_type(&self, ctx: &Context, args: &[BasicTypeEnum], variadic: bool) -> FunctionType {
-        match self {
-            CompilerTy::Ty(ty) => match ty {
-                Ty::I32 => ctx.i32_type().fn_type(args, variadic),
-                Ty::Str => {
-                    ctx.i8_type()
-                        .ptr_type(AddressSpace::Generic) // TODO: choose address space
-                        .fn_type(args, variadic)
-                }
-                Ty::Void => ctx.void_type().fn_type(args, variadic),
-            },
-            CompilerTy::FunctionType { args: args_, ret } => {
-                assert!(args.is_empty()); // FIXME
+fn str_type(ctx: &Context) -> StructType {
+    ctx.struct_type(&[c_str_type(ctx).into(), ctx.i32_type().into()], false)
+}
 
-                let mut args = vec![];
-                for arg in *args_ {
-                    args.push(arg.as_basic_type(ctx));
-                }
+fn str_list_type(ctx: &Context) -> StructType {
+    let strs = str_type(ctx).ptr_type(AddressSpace::Generic);
+    ctx.struct_type(&[strs.into(), ctx.i32_type().into()], false)
+}
 
-                ret.as_fn_type(ctx, &args, variadic)
-            }
-        }
+fn c_str_type(ctx: &Context) -> PointerType {
+    ctx.i8_type().ptr_type(AddressSpace::Generic) // TODO: choose address space
+}
+
+// the C `main` is generated by `add_entry` and calls into the kitty one
+fn symbol(name: &str) -> &str {
+    if name == "main" {
+        "kitty_main"
+    } else {
+        name
     }
 }
 
 impl Compiled {
-    pub fn create_target_machine(&mut self) {
-        let opt = OptimizationLevel::None; // TODO: custom opt leve
-        let reloc = RelocMode::Default;
-        let model = CodeModel::Default;
-
-        let target = Target::from_name("x86-64").unwrap();
-        self.tm = Some(
-            target
-                .create_target_machine("x86_64-pc-linux-gnu", "x86-64", "", opt, reloc, model)
-                .unwrap(),
-        );
+    pub(super) fn set_target_machine(&mut self, triple: &str, tm: TargetMachine) {
+        for module in &[&self.module, &self.runtime] {
+            module.set_triple(triple);
+            module.set_data_layout(&tm.get_target_data().get_data_layout());
+        }
+
+        self.tm = Some(tm);
     }
 
-    pub fn write_to_file(&self, path: &Path) -> Result<()> {
-        let tm = if let Some(tm) = &self.tm {
-            tm
-        } else {
-            return Err(Error::missing_target_machine());
-        };
+    pub fn write_to_file(&self, path: &Path, emit: Emit) -> Result<()> {
+        write_module(&self.module, self.tm.as_ref(), path, emit)
+    }
 
-        match tm.write_to_file(
-            &self.module,
-            FileType::Object, // FIXME
-            path,
-        ) {
-            Ok(()) => Ok(()),
-            Err(err) => Err(Error::llvm(err)),
-        }
+    pub fn write_runtime_to_file(&self, path: &Path) -> Result<()> {
+        write_module(&self.runtime, self.tm.as_ref(), path, Emit::Obj)
     }
 }
 
-impl<'t> From<Ty> for CompilerTy<'t> {
-    fn from(ty: Ty) -> CompilerTy<'t> {
-        CompilerTy::Ty(ty)
+fn write_module(
+    module: &Module,
+    tm: Option<&TargetMachine>,
+    path: &Path,
+    emit: Emit,
+) -> Result<()> {
+    let file_type = match emit {
+        Emit::LlvmIr => return module.print_to_file(path).map_err(Error::llvm),
+        Emit::LlvmBc if module.write_bitcode_to_path(path) => return Ok(()),
+        Emit::LlvmBc => return Err(Error::bitcode(path.into())),
+        Emit::Asm => FileType::Assembly,
+        Emit::Obj => FileType::Object,
+    };
+
+    let tm = tm.ok_or_else(Error::missing_target_machine)?;
+
+    match tm.write_to_file(module, file_type, path) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(Error::llvm(err)),
     }
 }
 
 impl<'c> Display for Compiler<'c> {
-    fn fmt(&self, _: &mut Formatter) -> fmt::Result {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         // TODO: ctx
         // TODO: builder
 
         if let Some(module) = &self.module {
-            module.print_to_stderr(); // FIXME
+            write!(fmt, "{}", module.print_to_string().to_string())?;
         }
 
         for module in &self.modules {
-            module.print_to_stderr(); // FIXME
+            write!(fmt, "{}", module.print_to_string().to_string())?;
         }
 
         // TODO: funcs
@@ -431,9 +691,8 @@ impl<'c> Display for Compiler<'c> {
 }
 
 impl Display for Compiled {
-    fn fmt(&self, _: &mut Formatter) -> fmt::Result {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         // TODO: tm
-        self.module.print_to_stderr(); // FIXME
-        Ok(())
+        write!(fmt, "{}", self.module.print_to_string().to_string())
     }
 }
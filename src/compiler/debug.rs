@@ -0,0 +1,392 @@
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::ptr;
+
+use fnv::FnvHashMap;
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::AsValueRef;
+use inkwell::values::BasicValueEnum;
+use inkwell::values::FunctionValue;
+use llvm_sys::core::*;
+use llvm_sys::debuginfo::*;
+use llvm_sys::prelude::*;
+
+use crate::lexer::Ident;
+use crate::lexer::Position;
+
+use super::error::*;
+
+const PRODUCER: &str = concat!("shedder ", env!("CARGO_PKG_VERSION"));
+const DEBUG_INFO_VERSION: u64 = 3;
+const DWARF_VERSION: u64 = 4;
+const MODULE_FLAG_WARNING: u64 = 2;
+
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED_CHAR: u32 = 0x08;
+
+#[derive(Debug)]
+pub(super) struct DebugInfo<'d> {
+    dir: String,
+    file: String,
+    ptr_bits: u64,
+    module: Option<ModuleInfo>,
+    funcs: FnvHashMap<Ident<'d>, FuncInfo>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ModuleInfo {
+    ctx: LLVMContextRef,
+    builder: LLVMDIBuilderRef,
+    file: LLVMMetadataRef,
+    dbg: u32,
+    i32_ty: LLVMMetadataRef,
+    i64_ty: LLVMMetadataRef,
+    str_ty: LLVMMetadataRef,
+}
+
+#[derive(Debug)]
+struct FuncInfo {
+    func: LLVMValueRef,
+    scope: LLVMMetadataRef,
+}
+
+impl<'d> DebugInfo<'d> {
+    pub(super) fn new(path: &Path, ptr_bits: u64) -> DebugInfo<'d> {
+        let dir = match path.parent() {
+            Some(dir) if dir != Path::new("") => dir.display().to_string(),
+            _ => ".".into(),
+        };
+
+        let file = match path.file_name() {
+            Some(file) => file.to_string_lossy().into_owned(),
+            None => path.display().to_string(),
+        };
+
+        DebugInfo {
+            dir,
+            file,
+            ptr_bits,
+            module: None,
+            funcs: FnvHashMap::default(),
+        }
+    }
+
+    pub(super) fn func(&mut self, name: Ident<'d>, func: FunctionValue, pos: Position) {
+        let linkage = func.get_name().to_owned();
+        let func = func.as_value_ref();
+        let module = *self.module(func);
+
+        unsafe {
+            let ty = LLVMDIBuilderCreateSubroutineType(
+                module.builder,
+                module.file,
+                ptr::null_mut(),
+                0,
+                LLVMDIFlagZero,
+            );
+
+            let subprogram = LLVMDIBuilderCreateFunction(
+                module.builder,
+                module.file,
+                name.inner().as_ptr() as *const c_char,
+                name.inner().len(),
+                linkage.as_ptr(),
+                linkage.as_bytes().len(),
+                module.file,
+                line(pos),
+                ty,
+                0,
+                1,
+                line(pos),
+                LLVMDIFlagZero,
+                0,
+            );
+            LLVMSetSubprogram(func, subprogram);
+
+            let scope = LLVMDIBuilderCreateLexicalBlock(
+                module.builder,
+                subprogram,
+                module.file,
+                line(pos),
+                col(pos),
+            );
+
+            self.funcs.insert(name, FuncInfo { func, scope });
+        }
+    }
+
+    pub(super) fn locate(&self, func: &Ident, block: &BasicBlock, pos: Position) -> Result<()> {
+        let (module, func) = match (&self.module, self.funcs.get(func)) {
+            (Some(module), Some(func)) => (module, func),
+            _ => return Ok(()),
+        };
+
+        unsafe {
+            let loc = LLVMDIBuilderCreateDebugLocation(
+                module.ctx,
+                line(pos),
+                col(pos),
+                func.scope,
+                ptr::null_mut(),
+            );
+            let loc = LLVMMetadataAsValue(module.ctx, loc);
+
+            let mut inst = LLVMGetLastInstruction(raw_block(func.func, block)?);
+            while !inst.is_null() && LLVMGetMetadata(inst, module.dbg).is_null() {
+                LLVMSetMetadata(inst, module.dbg, loc);
+                inst = LLVMGetPreviousInstruction(inst);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn var(
+        &self,
+        func: &Ident,
+        block: &BasicBlock,
+        name: &Ident,
+        arg: Option<u32>,
+        value: BasicValueEnum,
+        pos: Position,
+    ) -> Result<()> {
+        let (module, func) = match (&self.module, self.funcs.get(func)) {
+            (Some(module), Some(func)) => (module, func),
+            _ => return Ok(()),
+        };
+
+        let ty = match value {
+            BasicValueEnum::IntValue(int) if int.get_type().get_bit_width() == 64 => module.i64_ty,
+            BasicValueEnum::IntValue(_) => module.i32_ty,
+            BasicValueEnum::StructValue(_) => module.str_ty,
+            _ => return Ok(()),
+        };
+
+        unsafe {
+            let var = match arg {
+                Some(arg) => LLVMDIBuilderCreateParameterVariable(
+                    module.builder,
+                    func.scope,
+                    name.inner().as_ptr() as *const c_char,
+                    name.inner().len(),
+                    arg + 1,
+                    module.file,
+                    line(pos),
+                    ty,
+                    1,
+                    LLVMDIFlagZero,
+                ),
+                None => LLVMDIBuilderCreateAutoVariable(
+                    module.builder,
+                    func.scope,
+                    name.inner().as_ptr() as *const c_char,
+                    name.inner().len(),
+                    module.file,
+                    line(pos),
+                    ty,
+                    1,
+                    LLVMDIFlagZero,
+                    0,
+                ),
+            };
+
+            let expr = LLVMDIBuilderCreateExpression(module.builder, ptr::null_mut(), 0);
+            let loc = LLVMDIBuilderCreateDebugLocation(
+                module.ctx,
+                line(pos),
+                col(pos),
+                func.scope,
+                ptr::null_mut(),
+            );
+
+            LLVMDIBuilderInsertDbgValueAtEnd(
+                module.builder,
+                value.as_value_ref(),
+                var,
+                expr,
+                loc,
+                raw_block(func.func, block)?,
+            );
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn finish_module(&mut self) {
+        if let Some(module) = self.module.take() {
+            unsafe {
+                LLVMDIBuilderFinalize(module.builder);
+                LLVMDisposeDIBuilder(module.builder);
+            }
+        }
+
+        self.funcs.clear();
+    }
+
+    fn module(&mut self, func: LLVMValueRef) -> &ModuleInfo {
+        if self.module.is_none() {
+            self.module = Some(unsafe { self.create_module(func) });
+        }
+
+        self.module.as_ref().unwrap()
+    }
+
+    unsafe fn create_module(&self, func: LLVMValueRef) -> ModuleInfo {
+        let module = LLVMGetGlobalParent(func);
+        let ctx = LLVMGetModuleContext(module);
+        let builder = LLVMCreateDIBuilder(module);
+
+        let file = LLVMDIBuilderCreateFile(
+            builder,
+            self.file.as_ptr() as *const c_char,
+            self.file.len(),
+            self.dir.as_ptr() as *const c_char,
+            self.dir.len(),
+        );
+
+        LLVMDIBuilderCreateCompileUnit(
+            builder,
+            LLVMDWARFSourceLanguage::LLVMDWARFSourceLanguageC,
+            file,
+            PRODUCER.as_ptr() as *const c_char,
+            PRODUCER.len(),
+            0,
+            ptr::null(),
+            0,
+            0,
+            ptr::null(),
+            0,
+            LLVMDWARFEmissionKind::LLVMDWARFEmissionFull,
+            0,
+            0,
+            0,
+        );
+
+        add_flag(module, ctx, "Debug Info Version", DEBUG_INFO_VERSION);
+        add_flag(module, ctx, "Dwarf Version", DWARF_VERSION);
+
+        let i32_ty = LLVMDIBuilderCreateBasicType(
+            builder,
+            "i32".as_ptr() as *const c_char,
+            3,
+            32,
+            DW_ATE_SIGNED,
+        );
+
+        let i64_ty = LLVMDIBuilderCreateBasicType(
+            builder,
+            "i64".as_ptr() as *const c_char,
+            3,
+            64,
+            DW_ATE_SIGNED,
+        );
+
+        let char_ty = LLVMDIBuilderCreateBasicType(
+            builder,
+            "char".as_ptr() as *const c_char,
+            4,
+            8,
+            DW_ATE_UNSIGNED_CHAR,
+        );
+
+        let ptr_bits = self.ptr_bits;
+        let ptr_ty =
+            LLVMDIBuilderCreatePointerType(builder, char_ty, ptr_bits, 0, 0, ptr::null(), 0);
+
+        let mut members = [
+            member(builder, file, "ptr", 0, ptr_bits, ptr_ty),
+            member(builder, file, "len", ptr_bits, 32, i32_ty),
+        ];
+        let str_ty = LLVMDIBuilderCreateStructType(
+            builder,
+            file,
+            "str".as_ptr() as *const c_char,
+            3,
+            file,
+            0,
+            ptr_bits * 2,
+            ptr_bits as u32,
+            LLVMDIFlagZero,
+            ptr::null_mut(),
+            members.as_mut_ptr(),
+            members.len() as u32,
+            0,
+            ptr::null_mut(),
+            ptr::null(),
+            0,
+        );
+
+        ModuleInfo {
+            ctx,
+            builder,
+            file,
+            dbg: LLVMGetMDKindIDInContext(ctx, "dbg".as_ptr() as *const c_char, 3),
+            i32_ty,
+            i64_ty,
+            str_ty,
+        }
+    }
+}
+
+unsafe fn member(
+    builder: LLVMDIBuilderRef,
+    file: LLVMMetadataRef,
+    name: &str,
+    offset: u64,
+    size: u64,
+    ty: LLVMMetadataRef,
+) -> LLVMMetadataRef {
+    LLVMDIBuilderCreateMemberType(
+        builder,
+        file,
+        name.as_ptr() as *const c_char,
+        name.len(),
+        file,
+        0,
+        size,
+        size as u32,
+        offset,
+        LLVMDIFlagZero,
+        ty,
+    )
+}
+
+unsafe fn add_flag(module: LLVMModuleRef, ctx: LLVMContextRef, name: &str, value: u64) {
+    let i32_ty = LLVMInt32TypeInContext(ctx);
+    let mut values = [
+        LLVMConstInt(i32_ty, MODULE_FLAG_WARNING, 0),
+        LLVMMDStringInContext(ctx, name.as_ptr() as *const c_char, name.len() as u32),
+        LLVMConstInt(i32_ty, value, 0),
+    ];
+
+    let flag = LLVMMDNodeInContext(ctx, values.as_mut_ptr(), values.len() as u32);
+    let flags = CString::new("llvm.module.flags").unwrap();
+    LLVMAddNamedMetadataOperand(module, flags.as_ptr(), flag);
+}
+
+unsafe fn raw_block(func: LLVMValueRef, block: &BasicBlock) -> Result<LLVMBasicBlockRef> {
+    let mut raw = LLVMGetFirstBasicBlock(func);
+    while !raw.is_null() {
+        if CStr::from_ptr(LLVMGetBasicBlockName(raw)) == block.get_name() {
+            return Ok(raw);
+        }
+
+        raw = LLVMGetNextBasicBlock(raw);
+    }
+
+    let func = CStr::from_ptr(LLVMGetValueName(func));
+    Err(Error::missing_block(
+        func.to_string_lossy().into_owned(),
+        block.get_name().to_string_lossy().into_owned(),
+    ))
+}
+
+fn line(pos: Position) -> u32 {
+    pos.line as u32 + 1
+}
+
+fn col(pos: Position) -> u32 {
+    pos.col as u32 + 1
+}
@@ -1,6 +1,8 @@
 use crate::ast::Func;
+use crate::ast::Stmt;
 use crate::ast::Tree;
 use crate::lexer::Ident;
+use crate::lexer::Ty;
 
 use super::compile::Compiler;
 use super::compile::State;
@@ -11,8 +13,9 @@ impl<'f> Func<'f> {
         compiler.add_function(
             self.name.as_ref(),
             &self.args,
-            self.ret.into(),
+            self.ret,
             false, // FIXME: optionally variadic
+            self.pos,
         );
     }
 
@@ -25,21 +28,39 @@ impl<'f> Func<'f> {
             block: entry,
         };
 
-        let mut next = self.start;
-        while let Some(next_) = next {
-            let stmt = ast.stmts[next_].as_ref().unwrap(); // FIXME
-            stmt.prepare(compiler, &mut state);
-            next = stmt.next();
+        for (a, arg) in self.args.iter().enumerate() {
+            compiler.declare_var(&state, &arg.name, Some(a as u32), arg.pos)?;
         }
 
+        let mut last = self.pos;
         let mut next = self.start;
         while let Some(next_) = next {
+            if compiler.terminated(&state) {
+                break; // nothing after a `return` or a panic can run
+            }
+
             let stmt = ast.stmts[next_].as_ref().unwrap(); // FIXME
+            stmt.prepare(compiler, &mut state)?;
             stmt.compile(compiler, &mut state)?;
+            compiler.locate(&state, stmt.pos())?;
+            last = stmt.pos().or(last);
+
+            if let Stmt::Let { let_, .. } = stmt {
+                compiler.declare_var(&state, &let_.name, None, let_.pos)?;
+            }
+
             next = stmt.next();
         }
 
-        compiler.ret(&state, None); // FIXME
+        if !compiler.terminated(&state) {
+            if self.ret == Ty::Void {
+                compiler.ret(&state, None);
+            } else {
+                compiler.unreachable(&state); // the ast checks that values are returned
+            }
+
+            compiler.locate(&state, last)?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,43 @@
+use std::str::FromStr;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Emit {
+    LlvmIr,
+    LlvmBc,
+    Asm,
+    Obj,
+}
+
+const EMITS: &[(&str, Emit, &str)] = &[
+    ("llvm-ir", Emit::LlvmIr, "ll"),
+    ("llvm-bc", Emit::LlvmBc, "bc"),
+    ("asm", Emit::Asm, "s"),
+    ("obj", Emit::Obj, "o"),
+];
+
+impl Emit {
+    pub fn extension(self) -> &'static str {
+        EMITS
+            .iter()
+            .find(|(_, emit, _)| *emit == self)
+            .map(|(_, _, extension)| *extension)
+            .unwrap()
+    }
+}
+
+impl FromStr for Emit {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Emit, String> {
+        EMITS
+            .iter()
+            .find(|(name_, _, _)| *name_ == name)
+            .map(|(_, emit, _)| *emit)
+            .ok_or_else(|| {
+                format!(
+                    "unknown emit kind `{}` (expected llvm-ir, llvm-bc, asm or obj)",
+                    name
+                )
+            })
+    }
+}
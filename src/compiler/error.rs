@@ -1,9 +1,12 @@
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::path::PathBuf;
 
 use inkwell::support::LLVMString;
 
+use super::target::supported_archs;
+
 pub type Result<OK> = std::result::Result<OK, Error>;
 
 #[derive(Eq, PartialEq, Clone, Debug)]
@@ -13,14 +16,24 @@ pub struct Error {
 
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum ErrorKind {
-    LLVM(String),
+    Llvm(String),
     MissingTargetMachine,
+    MissingMain,
+    MissingRuntime,
+    Bitcode(PathBuf),
+    UnsupportedTarget(String),
+    InvalidFunction { func: String, reason: String },
+    InvalidModule(String),
+    MissingBlock { func: String, block: String },
+    UnknownVar { func: String, var: String },
+    VoidValue { func: String, call: String },
+    Unformattable { func: String },
 }
 
 impl Error {
     pub(super) fn llvm(string: LLVMString) -> Error {
         Error {
-            kind: ErrorKind::LLVM(string.to_string()),
+            kind: ErrorKind::Llvm(string.to_string()),
         }
     }
 
@@ -29,13 +42,115 @@ impl Error {
             kind: ErrorKind::MissingTargetMachine,
         }
     }
+
+    pub(super) fn missing_main() -> Error {
+        Error {
+            kind: ErrorKind::MissingMain,
+        }
+    }
+
+    pub(super) fn missing_runtime() -> Error {
+        Error {
+            kind: ErrorKind::MissingRuntime,
+        }
+    }
+
+    pub(super) fn bitcode(path: PathBuf) -> Error {
+        Error {
+            kind: ErrorKind::Bitcode(path),
+        }
+    }
+
+    pub(super) fn unsupported_target(triple: String) -> Error {
+        Error {
+            kind: ErrorKind::UnsupportedTarget(triple),
+        }
+    }
+
+    pub(super) fn invalid_function(func: String, reason: String) -> Error {
+        Error {
+            kind: ErrorKind::InvalidFunction { func, reason },
+        }
+    }
+
+    pub(super) fn invalid_module(reason: LLVMString) -> Error {
+        Error {
+            kind: ErrorKind::InvalidModule(reason.to_string()),
+        }
+    }
+
+    pub(super) fn missing_block(func: String, block: String) -> Error {
+        Error {
+            kind: ErrorKind::MissingBlock { func, block },
+        }
+    }
+
+    pub(super) fn unknown_var(func: String, var: String) -> Error {
+        Error {
+            kind: ErrorKind::UnknownVar { func, var },
+        }
+    }
+
+    pub(super) fn void_value(func: String, call: String) -> Error {
+        Error {
+            kind: ErrorKind::VoidValue { func, call },
+        }
+    }
+
+    pub(super) fn unformattable(func: String) -> Error {
+        Error {
+            kind: ErrorKind::Unformattable { func },
+        }
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match &self.kind {
-            ErrorKind::LLVM(err) => write!(fmt, "LLVM error: {}", err),
+            ErrorKind::Llvm(err) => write!(fmt, "LLVM error: {}", err),
             ErrorKind::MissingTargetMachine => write!(fmt, "missing target machine"),
+            ErrorKind::MissingMain => write!(fmt, "missing main function"),
+            ErrorKind::MissingRuntime => write!(fmt, "couldn't load the kitty runtime"),
+            ErrorKind::Bitcode(path) => {
+                write!(fmt, "couldn't write bitcode to {}", path.display())
+            }
+            ErrorKind::UnsupportedTarget(triple) => write!(
+                fmt,
+                "unsupported target `{}` (supported architectures: {})",
+                triple,
+                supported_archs().join(", ")
+            ),
+            ErrorKind::InvalidFunction { func, reason } => {
+                write!(fmt, "generated invalid LLVM IR for func {}", func)?;
+                if !reason.is_empty() {
+                    write!(fmt, ": {}", reason.trim_end())?;
+                }
+
+                Ok(())
+            }
+            ErrorKind::InvalidModule(reason) => {
+                write!(
+                    fmt,
+                    "generated an invalid LLVM module: {}",
+                    reason.trim_end()
+                )
+            }
+            ErrorKind::MissingBlock { func, block } => {
+                write!(fmt, "missing block {} in func {}", block, func)
+            }
+            ErrorKind::UnknownVar { func, var } => {
+                write!(fmt, "unknown variable {} in func {}", var, func)
+            }
+            ErrorKind::VoidValue { func, call } => write!(
+                fmt,
+                "the call to {} in func {} doesn't return a value",
+                call, func
+            ),
+            ErrorKind::Unformattable { func } => write!(
+                fmt,
+                "only ints and strs can be inserted into strings, in func {}",
+                func
+            ),
         }
     }
 }
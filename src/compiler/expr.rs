@@ -1,7 +1,7 @@
-use inkwell::types::AnyTypeEnum;
-use inkwell::types::BasicTypeEnum;
 use inkwell::values::BasicValueEnum;
 
+use crate::lexer::FormatSpec;
+use crate::lexer::Ident;
 use crate::parser::expr::DynStringSeg;
 use crate::parser::expr::Expr;
 use crate::parser::expr::Func;
@@ -10,13 +10,18 @@ use crate::parser::expr::Literal;
 use super::compile::Compiler;
 use super::compile::State;
 use super::error::*;
+use super::runtime;
 
 impl<'e> Expr<'e> {
-    pub(super) fn prepare(&'e self, compiler: &mut Compiler<'e>, state: &mut State<'e>) {
+    pub(super) fn prepare(
+        &'e self,
+        compiler: &mut Compiler<'e>,
+        state: &mut State<'e>,
+    ) -> Result<()> {
         match self {
             Expr::Literal(lit) => lit.prepare(compiler, state),
             Expr::Func(func) => func.prepare(compiler, state),
-            Expr::Var(_) => (),
+            Expr::Var(_) => Ok(()),
         }
     }
 
@@ -32,164 +37,155 @@ impl<'e> Expr<'e> {
         }
     }
 
-    fn as_string(&self, compiler: &Compiler, state: &State) -> String {
+    pub(super) fn value(
+        &'e self,
+        compiler: &mut Compiler<'e>,
+        state: &mut State<'e>,
+    ) -> Result<BasicValueEnum> {
         match self {
-            Expr::Literal(lit) => lit.as_string(compiler, state),
-            Expr::Func(_) => unimplemented!(),
-            Expr::Var(var) => {
-                let mut ty = match compiler.get_var(state, var).unwrap()[0] // FIXME
-                    .get_type()
-                {
-                    BasicTypeEnum::ArrayType(_) => unimplemented!(), // FIXME
-                    BasicTypeEnum::IntType(_) => return "%i".into(),
-                    BasicTypeEnum::FloatType(_) => unimplemented!(), // FIXME
-                    BasicTypeEnum::PointerType(ptr) => {
-                        // --- FIXME ---
-                        // (better detection)
-                        let ty = ptr.get_element_type();
-                        if let AnyTypeEnum::IntType(int) = ty {
-                            if int.get_bit_width() == 8 {
-                                return "%s".into();
-                            }
-                        }
-                        // --- FIXME ---
-
-                        ty
-                    }
-                    BasicTypeEnum::StructType(_) => unimplemented!(), // FIXME
-                    BasicTypeEnum::VectorType(_) => unimplemented!(), // FIXME
-                };
-
-                loop {
-                    match ty {
-                        AnyTypeEnum::ArrayType(_) => unimplemented!(), // FIXME
-                        AnyTypeEnum::FloatType(_) => unimplemented!(), // FIXME
-                        AnyTypeEnum::FunctionType(_) => unimplemented!(), // FIXME
-                        AnyTypeEnum::IntType(_) => return "%i".into(),
-                        AnyTypeEnum::PointerType(ptr) => ty = ptr.get_element_type(),
-                        AnyTypeEnum::StructType(_) => unimplemented!(), // FIXME
-                        AnyTypeEnum::VectorType(_) => unimplemented!(), // FIXME
-                        AnyTypeEnum::VoidType(_) => unimplemented!(),   // FIXME
-                    }
-                }
+            Expr::Literal(lit) => {
+                lit.prepare(compiler, state)?;
+                Ok(compiler.var(state, &lit.name())?[0])
             }
+            Expr::Func(func) => {
+                func.prepare(compiler, state)?;
+                func.value(compiler, state)
+            }
+            Expr::Var(var) => Ok(compiler.var(state, var)?[0]),
         }
     }
 }
 
 impl<'l> Literal<'l> {
-    pub(super) fn prepare(&'l self, compiler: &mut Compiler<'l>, state: &mut State<'l>) {
+    pub(super) fn prepare(
+        &'l self,
+        compiler: &mut Compiler<'l>,
+        state: &mut State<'l>,
+    ) -> Result<()> {
         match self {
-            Literal::Int { name, int } => {
-                compiler.const_int(state, name.as_ref(), *int as u64);
+            Literal::Int { name, id, int } => {
+                compiler.const_int(state, name.as_ref(), *id, *int as u64);
             }
             Literal::String { name, string } => {
-                compiler.add_global_string(state, name.as_ref(), &string.replace("\\\"", "\""));
+                compiler.add_global_string(state, name.as_ref(), string);
             }
             Literal::RefDynString { name, segs } => {
-                let mut string = String::new();
-                for seg in *segs {
-                    string.push_str(&seg.as_string(compiler, state));
-                }
-
-                compiler.add_global_string(state, name.as_ref(), &string);
+                format(name.as_ref(), segs, compiler, state)?;
             }
             Literal::OwnedDynString { name, segs } => {
-                let mut string = String::new();
-                for seg in segs {
-                    string.push_str(&seg.as_string(compiler, state));
-                }
-
-                compiler.add_global_string(state, name.as_ref(), &string);
+                format(name.as_ref(), segs, compiler, state)?;
             }
         }
-    }
-
-    fn as_string(&self, compiler: &Compiler, state: &State) -> String {
-        match self {
-            Literal::Int { .. } => "%i".into(),
-            Literal::String { .. } => "%s".into(),
-            Literal::RefDynString { segs, .. } => {
-                let mut string = String::new();
-
-                for seg in *segs {
-                    match seg {
-                        DynStringSeg::String(string_) => string.push_str(string_),
-                        DynStringSeg::Expr(expr) => {
-                            string.push_str(&expr.as_string(compiler, state))
-                        }
-                    }
-                }
-
-                string
-            }
-            Literal::OwnedDynString { segs, .. } => {
-                let mut string = String::new();
-
-                for seg in segs {
-                    match seg {
-                        DynStringSeg::String(string_) => string.push_str(string_),
-                        DynStringSeg::Expr(expr) => {
-                            string.push_str(&expr.as_string(compiler, state))
-                        }
-                    }
-                }
 
-                string
-            }
-        }
+        Ok(())
     }
-}
 
-impl<'s> DynStringSeg<'s> {
-    fn as_string(&self, compiler: &Compiler, state: &State) -> String {
-        // FIXME: borrow
+    pub(super) fn name(&'l self) -> Ident<'l> {
         match self {
-            DynStringSeg::String(string) => string.to_string(), // FIXME: borrow
-            DynStringSeg::Expr(expr) => expr.as_string(compiler, state),
+            Literal::Int { name, .. } => name.as_ref(),
+            Literal::String { name, .. } => name.as_ref(),
+            Literal::RefDynString { name, .. } => name.as_ref(),
+            Literal::OwnedDynString { name, .. } => name.as_ref(),
         }
     }
 }
 
 impl<'f> Func<'f> {
-    pub(super) fn prepare(&'f self, compiler: &mut Compiler<'f>, state: &mut State<'f>) {
+    pub(super) fn prepare(
+        &'f self,
+        compiler: &mut Compiler<'f>,
+        state: &mut State<'f>,
+    ) -> Result<()> {
         for arg in self.args.inner() {
             match arg {
-                Expr::Literal(lit) => lit.prepare(compiler, state),
-                Expr::Func(func) => func.prepare(compiler, state),
+                Expr::Literal(lit) => lit.prepare(compiler, state)?,
+                Expr::Func(func) => func.prepare(compiler, state)?,
                 Expr::Var(_) => (),
             }
         }
+
+        Ok(())
     }
 
     pub(super) fn compile(&self, compiler: &mut Compiler<'f>, state: &mut State<'f>) -> Result<()> {
-        self.call(compiler, state);
+        self.call(compiler, state)?;
         Ok(())
     }
 
-    pub(super) fn call(&self, compiler: &Compiler, state: &State) -> Option<BasicValueEnum> {
+    pub(super) fn call(
+        &self,
+        compiler: &Compiler,
+        state: &State,
+    ) -> Result<Option<BasicValueEnum>> {
         let mut args = vec![];
 
         for arg in self.args.inner() {
             match arg {
-                Expr::Literal(lit) => {
-                    for var in compiler.get_var(&state, &lit.name()).unwrap() {
-                        // FIXME
-                        args.push(var);
-                    }
-                }
-                Expr::Func(func) => {
-                    args.push(func.call(compiler, state).unwrap()); // FIXME
-                }
-                Expr::Var(var) => {
-                    for var in compiler.get_var(&state, var).unwrap() {
-                        // FIXME
-                        args.push(var);
-                    }
+                Expr::Literal(lit) => args.extend(compiler.var(state, &lit.name())?),
+                Expr::Func(func) => args.push(func.value(compiler, state)?),
+                Expr::Var(var) => args.extend(compiler.var(state, var)?),
+            }
+        }
+
+        let value = compiler.call(state, &self.name, &args, self.pos);
+
+        // the builtins copy what they keep, so the dyn strs built only for the call can go
+        if runtime::builtin(self.name.inner()).is_some() && !compiler.terminated(state) {
+            for arg in self.args.inner() {
+                if let Expr::Literal(
+                    lit @ Literal::RefDynString { .. } | lit @ Literal::OwnedDynString { .. },
+                ) = arg
+                {
+                    compiler.free_str(state, compiler.var(state, &lit.name())?[0]);
                 }
             }
         }
 
-        compiler.call(state, &self.name, &args)
+        Ok(value)
+    }
+
+    pub(super) fn value(&self, compiler: &Compiler, state: &State) -> Result<BasicValueEnum> {
+        self.call(compiler, state)?
+            .ok_or_else(|| Error::void_value(state.func.inner().into(), self.name.inner().into()))
+    }
+}
+
+fn format<'s>(
+    name: Ident<'s>,
+    segs: &'s [DynStringSeg<'s>],
+    compiler: &mut Compiler<'s>,
+    state: &mut State<'s>,
+) -> Result<()> {
+    let mut parts = vec![];
+
+    for (s, seg) in segs.iter().enumerate() {
+        match seg {
+            DynStringSeg::String(string) => {
+                let part = compiler.const_str(&format!("{}.{}", name.inner(), s), string);
+                parts.push((part, false));
+            }
+            DynStringSeg::Expr(expr, spec) => {
+                let value = expr.value(compiler, state)?;
+                let part = format_value(value, &spec.unwrap_or_default(), compiler, state)?;
+                parts.push((part, true));
+            }
+        }
+    }
+
+    compiler.format_string(state, name, &parts);
+    Ok(())
+}
+
+// the ast only lets ints and strs into a dyn string
+fn format_value(
+    value: BasicValueEnum,
+    spec: &FormatSpec,
+    compiler: &Compiler,
+    state: &State,
+) -> Result<BasicValueEnum> {
+    match value {
+        BasicValueEnum::IntValue(int) => Ok(compiler.format_int(state, int, spec)),
+        _ if compiler.is_str(value) => Ok(compiler.format_str(state, value, spec)),
+        _ => Err(Error::unformattable(state.func.inner().into())),
     }
 }
@@ -0,0 +1,74 @@
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::os::raw::c_int;
+use std::os::raw::c_void;
+use std::ptr;
+
+use inkwell::execution_engine::JitFunction;
+use inkwell::OptimizationLevel;
+
+use super::compile::Compiled;
+use super::error::*;
+
+extern "C" {
+    fn printf(format: *const c_char, ...) -> c_int;
+    fn dprintf(fd: c_int, format: *const c_char, ...) -> c_int;
+    fn fflush(stream: *mut c_void) -> c_int;
+    fn malloc(size: usize) -> *mut c_void;
+    fn free(ptr: *mut c_void);
+    fn abort() -> !;
+    fn memcpy(dest: *mut c_void, src: *const c_void, size: usize) -> *mut c_void;
+    fn memset(dest: *mut c_void, byte: c_int, size: usize) -> *mut c_void;
+    fn memcmp(left: *const c_void, right: *const c_void, size: usize) -> c_int;
+    fn strlen(string: *const c_char) -> usize;
+}
+
+type Main = unsafe extern "C" fn(c_int, *const *const c_char) -> c_int;
+
+impl Compiled {
+    pub fn run(&self, args: &[String]) -> Result<i32> {
+        let engine = self
+            .module
+            .create_jit_execution_engine(OptimizationLevel::None)
+            .map_err(Error::llvm)?;
+        engine
+            .add_module(&self.runtime)
+            .map_err(|()| Error::missing_runtime())?;
+
+        let symbols = [
+            ("printf", printf as usize),
+            ("dprintf", dprintf as usize),
+            ("fflush", fflush as usize),
+            ("malloc", malloc as usize),
+            ("free", free as usize),
+            ("abort", abort as usize),
+            ("memcpy", memcpy as usize),
+            ("memset", memset as usize),
+            ("memcmp", memcmp as usize),
+            ("strlen", strlen as usize),
+        ];
+
+        for (name, symbol) in &symbols {
+            for module in &[&self.module, &self.runtime] {
+                if let Some(func) = module.get_function(name) {
+                    engine.add_global_mapping(&func, *symbol);
+                }
+            }
+        }
+
+        let args = args
+            .iter()
+            .map(|arg| CString::new(arg.as_str()).unwrap()) // command line args can't contain NUL
+            .collect::<Vec<_>>();
+        let mut argv = args.iter().map(|arg| arg.as_ptr()).collect::<Vec<_>>();
+        argv.push(ptr::null());
+
+        unsafe {
+            let main: JitFunction<Main> = engine
+                .get_function("main")
+                .map_err(|_| Error::missing_main())?;
+
+            Ok(main.call(args.len() as c_int, argv.as_ptr()))
+        }
+    }
+}
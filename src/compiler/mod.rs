@@ -1,37 +1,163 @@
+use std::path::Path;
+
 use crate::ast::Tree;
-use crate::lexer::Ident;
-use crate::lexer::Ty;
 
 mod compile;
+mod debug;
 mod decl;
+mod emit;
 mod error;
 mod expr;
+mod jit;
 mod module;
+mod opt;
+mod runtime;
 mod stmt;
+mod target;
+mod wasm;
 
 pub use compile::Compiled;
+pub use emit::Emit;
 pub use error::*;
+pub use opt::OptLevel;
+pub use target::TargetSpec;
 
 use compile::Compiler;
-use compile::CompilerTy;
-
-pub fn compile(ast: &Tree) -> Result<Compiled> {
-    let mut compiler = Compiler::new();
-
-    // --- FIXME ---
-    compiler.new_module("_");
-    let ret = &[Ty::Str.into()];
-    compiler.add_external_function(
-        Ident::Owned("printf".into()),
-        CompilerTy::Ty(Ty::Void).fn_type(ret),
-        true,
-    );
-    // --- FIXME ---
+
+pub fn compile(
+    ast: &Tree,
+    file: &Path,
+    debug: bool,
+    level: OptLevel,
+    spec: &TargetSpec,
+) -> Result<Compiled> {
+    let tm = spec.target_machine(level)?;
+    let ptr_bits = u64::from(tm.get_target_data().get_pointer_byte_size(None)) * 8;
+
+    let mut compiler = Compiler::new(file, &ast.lits, debug, ptr_bits, spec.hosted());
 
     for module in ast.modules.values() {
         compiler.new_module(module.name.inner());
         module.compile(ast, &mut compiler)?;
     }
 
-    Ok(compiler.compiled())
+    let mut compiled = compiler.compiled()?;
+    compiled.set_target_machine(spec.triple(), tm);
+
+    Ok(compiled)
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::targets::InitializationConfig;
+    use inkwell::targets::Target;
+
+    use super::*;
+    use crate::lexer::lex;
+    use crate::parser::parse;
+
+    fn build(input: &str, level: OptLevel) -> Compiled {
+        let tokens = lex(input).unwrap();
+        let module = parse(&tokens).unwrap();
+        let mut ast = Tree::build(&[&module]);
+        ast.declare_builtins();
+        ast.infer();
+        ast.verify().unwrap();
+        ast.remove_builtins();
+
+        Target::initialize_native(&InitializationConfig::default()).unwrap();
+        let spec = TargetSpec::new(None, None, &[]).unwrap();
+        compile(&ast, Path::new("test.kt"), false, level, &spec).unwrap()
+    }
+
+    // compiles and verifies the whole program, then runs its `main` in the JIT
+    fn run(input: &str) -> i32 {
+        build(input, OptLevel::O0).run(&["test".into()]).unwrap()
+    }
+
+    const DECLS: &str = "func helper(): i32 { return 5; }\nfunc nothing() {}\n";
+
+    fn main(body: &str) -> String {
+        format!("{}func main(): i32 {{ {} }}", DECLS, body)
+    }
+
+    #[test]
+    fn lets_bind_every_expr() {
+        assert_eq!(run(&main("let x = 1; return x;")), 1);
+        assert_eq!(run(&main(r#"let x = "abc"; return len(x);"#)), 3);
+        assert_eq!(
+            run(&main(
+                r#"let n = 42; let x = `<${n:04x}>`; return compare(x, "<002a>");"#
+            )),
+            0
+        );
+        assert_eq!(run(&main("let y = 2; let x = y; return x;")), 2);
+        assert_eq!(run(&main("let x = helper(); return x;")), 5);
+    }
+
+    #[test]
+    fn returns_every_expr() {
+        assert_eq!(run(&main("return 1;")), 1);
+        assert_eq!(
+            run(r#"func s(): str { return "abc"; } func main(): i32 { return len(s()); }"#),
+            3
+        );
+        assert_eq!(
+            run(concat!(
+                "func s(n: i32): str { return `<${n}>`; }\n",
+                r#"func main(): i32 { return compare(s(42), "<42>"); }"#,
+            )),
+            0
+        );
+        assert_eq!(run(&main("let x = 3; return x;")), 3);
+        assert_eq!(run(&main("return helper();")), 5);
+        assert_eq!(
+            run(&format!(
+                "{}func f() {{ return nothing(); }}\nfunc main(): i32 {{ f(); return 0; }}",
+                DECLS
+            )),
+            0
+        );
+    }
+
+    #[test]
+    fn evaluates_every_expr_as_a_stmt() {
+        assert_eq!(run(&main("1; return 0;")), 0);
+        assert_eq!(run(&main(r#""abc"; return 0;"#)), 0);
+        assert_eq!(run(&main("let n = 1; `${n}`; return 0;")), 0);
+        assert_eq!(run(&main("let x = 1; x; return 0;")), 0);
+        assert_eq!(run(&main("helper(); return 0;")), 0);
+    }
+
+    // small enough to inline at -O2, too big for the -Os threshold
+    const GREET: &str = concat!(
+        "func greet(name: str): i32 {\n",
+        "    let line = concat(name, \"!\");\n",
+        "    printf(line);\n",
+        "    return len(line);\n",
+        "}\n",
+        "func main(): i32 { greet(\"a\"); return greet(\"b\"); }\n",
+    );
+
+    #[test]
+    fn optimizes_for_size_at_os() {
+        let optimized = |level| {
+            let compiled = build(GREET, level);
+            compiled.optimize(level);
+
+            let ir = compiled.module.print_to_string().to_string();
+            let code = compiled.run(&["test".into()]).unwrap();
+            (ir, code)
+        };
+
+        let (fast, fast_code) = optimized(OptLevel::O2);
+        let (small, small_code) = optimized(OptLevel::Os);
+
+        // only the definition is left once both calls are inlined
+        assert_eq!(fast.matches("@greet(").count(), 1);
+        assert_eq!(small.matches("@greet(").count(), 3);
+        assert!(!fast.contains("optsize"));
+        assert!(small.contains("optsize"));
+        assert_eq!(fast_code, small_code);
+    }
 }
@@ -1,5 +1,6 @@
 use crate::ast::Module;
 use crate::ast::Tree;
+use crate::lexer::Ident;
 
 use super::compile::Compiler;
 use super::error::*;
@@ -20,6 +21,10 @@ impl<'m> Module<'m> {
                 .compile(ast, compiler)?;
         }
 
+        if self.name.inner() == "main" {
+            compiler.add_entry(&Ident::Ref("main"));
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use inkwell::attributes::Attribute;
+use inkwell::attributes::AttributeLoc;
+use inkwell::module::Module;
+use inkwell::passes::PassManager;
+use inkwell::passes::PassManagerBuilder;
+use inkwell::OptimizationLevel;
+
+use super::compile::Compiled;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum OptLevel {
+    O0,
+    O1,
+    Os,
+    O2,
+    O3,
+}
+
+const OPT_LEVELS: &[(&str, OptLevel)] = &[
+    ("0", OptLevel::O0),
+    ("1", OptLevel::O1),
+    ("2", OptLevel::O2),
+    ("3", OptLevel::O3),
+    ("s", OptLevel::Os),
+];
+
+const OPTIMIZED: &[OptLevel] = &[OptLevel::O1, OptLevel::Os, OptLevel::O2, OptLevel::O3];
+const FAST: &[OptLevel] = &[OptLevel::O2, OptLevel::O3];
+const SMALL: &[OptLevel] = &[OptLevel::Os];
+const AGGRESSIVE: &[OptLevel] = &[OptLevel::O3];
+
+type AddPass = fn(&PassManager);
+
+// what clang inlines up to at -Os, a third of the default threshold
+const SIZE_INLINE_THRESHOLD: u32 = 75;
+
+// in the order they run, this is the whole pipeline for every level
+const PASSES: &[(&str, &[OptLevel], AddPass)] = &[
+    ("inline", FAST, PassManager::add_function_inlining_pass),
+    ("inline-size", SMALL, add_size_inlining_pass),
+    (
+        "argpromotion",
+        AGGRESSIVE,
+        PassManager::add_argument_promotion_pass,
+    ),
+    (
+        "mem2reg",
+        OPTIMIZED,
+        PassManager::add_promote_memory_to_register_pass,
+    ),
+    (
+        "instcombine",
+        OPTIMIZED,
+        PassManager::add_instruction_combining_pass,
+    ),
+    (
+        "simplifycfg",
+        OPTIMIZED,
+        PassManager::add_cfg_simplification_pass,
+    ),
+    ("gvn", OPTIMIZED, PassManager::add_gvn_pass),
+    ("licm", AGGRESSIVE, PassManager::add_licm_pass),
+    ("loop-unroll", AGGRESSIVE, PassManager::add_loop_unroll_pass),
+    (
+        "dce",
+        OPTIMIZED,
+        PassManager::add_dead_code_elimination_pass,
+    ),
+    ("globaldce", SMALL, PassManager::add_global_dce_pass),
+    ("constmerge", SMALL, PassManager::add_constant_merge_pass),
+];
+
+// the builder is set up like -Os, without an optimization level it only adds
+// its inliner
+fn add_size_inlining_pass(pm: &PassManager) {
+    let builder = PassManagerBuilder::create();
+    builder.set_optimization_level(OptimizationLevel::None);
+    builder.set_size_level(OptLevel::Os.size_level());
+    builder.set_inliner_with_threshold(SIZE_INLINE_THRESHOLD);
+    builder.populate_module_pass_manager(pm);
+}
+
+impl OptLevel {
+    pub(super) fn llvm(self) -> OptimizationLevel {
+        match self {
+            OptLevel::O0 => OptimizationLevel::None,
+            OptLevel::O1 => OptimizationLevel::Less,
+            // codegen is kept small by the `optsize` attribute, not the level
+            OptLevel::Os => OptimizationLevel::Default,
+            OptLevel::O2 => OptimizationLevel::Default,
+            OptLevel::O3 => OptimizationLevel::Aggressive,
+        }
+    }
+
+    pub(super) fn size_level(self) -> u32 {
+        match self {
+            OptLevel::Os => 1,
+            _ => 0,
+        }
+    }
+
+    pub(super) fn passes(self) -> Vec<&'static str> {
+        PASSES
+            .iter()
+            .filter(|(_, levels, _)| levels.contains(&self))
+            .map(|(name, _, _)| *name)
+            .collect()
+    }
+}
+
+impl Compiled {
+    pub fn optimize(&self, level: OptLevel) -> Vec<&'static str> {
+        if level.size_level() > 0 {
+            optimize_for_size(&self.module);
+            optimize_for_size(&self.runtime);
+        }
+
+        let pm = PassManager::create_for_module();
+
+        for (_, levels, add) in PASSES {
+            if levels.contains(&level) {
+                add(&pm);
+            }
+        }
+
+        pm.run_on_module(&self.module);
+        pm.run_on_module(&self.runtime);
+        level.passes()
+    }
+}
+
+// `optsize` keeps the inliner and codegen from trading size for speed
+fn optimize_for_size(module: &Module) {
+    let kind = Attribute::get_named_enum_kind_id("optsize");
+    let attr = module.get_context().create_enum_attribute(kind, 0);
+
+    let mut func = module.get_first_function();
+    while let Some(func_) = func {
+        func_.add_attribute(AttributeLoc::Function, attr);
+        func = func_.get_next_function();
+    }
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<OptLevel, String> {
+        OPT_LEVELS
+            .iter()
+            .find(|(name_, _)| *name_ == name)
+            .map(|(_, level)| *level)
+            .ok_or_else(|| {
+                format!(
+                    "unknown optimization level `{}` (expected 0, 1, 2, 3 or s)",
+                    name
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_nothing_at_o0() {
+        assert!(OptLevel::O0.passes().is_empty());
+    }
+
+    #[test]
+    fn runs_gvn_without_inlining_at_o1() {
+        let passes = OptLevel::O1.passes();
+        assert_eq!(
+            passes,
+            vec!["mem2reg", "instcombine", "simplifycfg", "gvn", "dce"]
+        );
+    }
+
+    #[test]
+    fn inlines_less_at_os_and_drops_dead_globals() {
+        let passes = OptLevel::Os.passes();
+        assert_ne!(passes, OptLevel::O2.passes());
+        assert_eq!(passes[0], "inline-size");
+        assert!(!passes.contains(&"inline"));
+        assert!(!passes.contains(&"loop-unroll"));
+        assert!(passes.contains(&"globaldce"));
+    }
+
+    #[test]
+    fn sets_a_size_level_only_at_os() {
+        assert_eq!(OptLevel::Os.size_level(), 1);
+        assert_eq!(OptLevel::O2.size_level(), 0);
+    }
+
+    #[test]
+    fn runs_every_pass_but_the_size_ones_at_o3() {
+        let passes = PASSES
+            .iter()
+            .filter(|(_, levels, _)| *levels != SMALL)
+            .map(|(name, _, _)| *name)
+            .collect::<Vec<_>>();
+        assert_eq!(OptLevel::O3.passes(), passes);
+    }
+}
@@ -0,0 +1,377 @@
+; kitty runtime
+;
+; Built alongside every program and linked in by the build driver. Nothing here
+; calls into a libc, so the same code runs with or without one.
+;
+; A `str` is a `{ i8*, i32 }` pair of a pointer and a length in bytes. Every
+; string handed out by the runtime is also NUL terminated, so passing one to C
+; only needs the pointer.
+;
+; A `[str]` is a `{ { i8*, i32 }*, i32 }` pair of a pointer to the first string
+; and a count.
+
+; The `%size_t` type and the `@kitty_size` and `@kitty_int` casts between it
+; and `i32` are prepended by the compiler, they depend on the pointer width of
+; the target. So are the memory primitives, `@kitty_free`, `@kitty_print`,
+; `@kitty_abort` and `@kitty_panic`, from runtime_hosted.ll or, for targets
+; without a libc, runtime_freestanding.ll.
+
+@kitty_minus = private unnamed_addr constant [1 x i8] c"-"
+@kitty_out_of_memory = private unnamed_addr constant [13 x i8] c"out of memory"
+@kitty_out_of_bounds = private unnamed_addr constant [19 x i8] c"index out of bounds"
+
+define i8* @kitty_alloc(%size_t %size) {
+entry:
+  %ptr = call i8* @kitty_mem_alloc(%size_t %size)
+  %failed = icmp eq i8* %ptr, null
+  br i1 %failed, label %out_of_memory, label %done
+
+out_of_memory:
+  %message.ptr = getelementptr [13 x i8], [13 x i8]* @kitty_out_of_memory, i64 0, i64 0
+  %message = call { i8*, i32 } @kitty_str_new(i8* %message.ptr, i32 13)
+  call void @kitty_abort({ i8*, i32 } %message)
+  unreachable
+
+done:
+  ret i8* %ptr
+}
+
+define { i8*, i32 } @kitty_str_new(i8* %ptr, i32 %len) {
+entry:
+  %str.0 = insertvalue { i8*, i32 } undef, i8* %ptr, 0
+  %str = insertvalue { i8*, i32 } %str.0, i32 %len, 1
+  ret { i8*, i32 } %str
+}
+
+define i8* @kitty_str_to_cstr({ i8*, i32 } %str) {
+entry:
+  %ptr = extractvalue { i8*, i32 } %str, 0
+  ret i8* %ptr
+}
+
+define { i8*, i32 } @kitty_str_from_cstr(i8* %ptr) {
+entry:
+  %len.wide = call %size_t @kitty_cstr_len(i8* %ptr)
+  %len = call i32 @kitty_int(%size_t %len.wide)
+  %str = call { i8*, i32 } @kitty_str_new(i8* %ptr, i32 %len)
+  ret { i8*, i32 } %str
+}
+
+define private { i8*, i32 } @kitty_str_copy(i8* %ptr, i32 %len) {
+entry:
+  %len.wide = call %size_t @kitty_size(i32 %len)
+  %size = add %size_t %len.wide, 1
+  %buf = call i8* @kitty_alloc(%size_t %size)
+  call void @kitty_mem_copy(i8* %buf, i8* %ptr, %size_t %len.wide)
+  %end = getelementptr i8, i8* %buf, %size_t %len.wide
+  store i8 0, i8* %end
+  %str = call { i8*, i32 } @kitty_str_new(i8* %buf, i32 %len)
+  ret { i8*, i32 } %str
+}
+
+define i32 @kitty_str_len({ i8*, i32 } %str) {
+entry:
+  %len = extractvalue { i8*, i32 } %str, 1
+  ret i32 %len
+}
+
+define { i8*, i32 } @kitty_str_concat({ i8*, i32 } %left, { i8*, i32 } %right) {
+entry:
+  %left.ptr = extractvalue { i8*, i32 } %left, 0
+  %left.len = extractvalue { i8*, i32 } %left, 1
+  %right.ptr = extractvalue { i8*, i32 } %right, 0
+  %right.len = extractvalue { i8*, i32 } %right, 1
+  %left.len.wide = call %size_t @kitty_size(i32 %left.len)
+  %right.len.wide = call %size_t @kitty_size(i32 %right.len)
+  %len = add i32 %left.len, %right.len
+  %len.wide = call %size_t @kitty_size(i32 %len)
+  %size = add %size_t %len.wide, 1
+  %buf = call i8* @kitty_alloc(%size_t %size)
+  call void @kitty_mem_copy(i8* %buf, i8* %left.ptr, %size_t %left.len.wide)
+  %mid = getelementptr i8, i8* %buf, %size_t %left.len.wide
+  call void @kitty_mem_copy(i8* %mid, i8* %right.ptr, %size_t %right.len.wide)
+  %end = getelementptr i8, i8* %buf, %size_t %len.wide
+  store i8 0, i8* %end
+  %str = call { i8*, i32 } @kitty_str_new(i8* %buf, i32 %len)
+  ret { i8*, i32 } %str
+}
+
+define i32 @kitty_str_compare({ i8*, i32 } %left, { i8*, i32 } %right) {
+entry:
+  %left.ptr = extractvalue { i8*, i32 } %left, 0
+  %left.len = extractvalue { i8*, i32 } %left, 1
+  %right.ptr = extractvalue { i8*, i32 } %right, 0
+  %right.len = extractvalue { i8*, i32 } %right, 1
+  %shorter = icmp slt i32 %left.len, %right.len
+  %longer = icmp sgt i32 %left.len, %right.len
+  %min = select i1 %shorter, i32 %left.len, i32 %right.len
+  %min.wide = call %size_t @kitty_size(i32 %min)
+  %cmp = call i32 @kitty_mem_compare(i8* %left.ptr, i8* %right.ptr, %size_t %min.wide)
+  %same = icmp eq i32 %cmp, 0
+  br i1 %same, label %prefix, label %differ
+
+differ:
+  %less = icmp slt i32 %cmp, 0
+  %sign = select i1 %less, i32 -1, i32 1
+  ret i32 %sign
+
+prefix:
+  %greater = zext i1 %longer to i32
+  %lesser = zext i1 %shorter to i32
+  %order = sub i32 %greater, %lesser
+  ret i32 %order
+}
+
+; out of range bounds are clamped to the string
+define { i8*, i32 } @kitty_str_slice({ i8*, i32 } %str, i32 %start, i32 %end) {
+entry:
+  %ptr = extractvalue { i8*, i32 } %str, 0
+  %len = extractvalue { i8*, i32 } %str, 1
+  %start.neg = icmp slt i32 %start, 0
+  %start.0 = select i1 %start.neg, i32 0, i32 %start
+  %start.over = icmp sgt i32 %start.0, %len
+  %start.1 = select i1 %start.over, i32 %len, i32 %start.0
+  %end.under = icmp slt i32 %end, %start.1
+  %end.0 = select i1 %end.under, i32 %start.1, i32 %end
+  %end.over = icmp sgt i32 %end.0, %len
+  %end.1 = select i1 %end.over, i32 %len, i32 %end.0
+  %from = getelementptr i8, i8* %ptr, i32 %start.1
+  %count = sub i32 %end.1, %start.1
+  %slice = call { i8*, i32 } @kitty_str_copy(i8* %from, i32 %count)
+  ret { i8*, i32 } %slice
+}
+
+; the byte at `%i`, or -1 past the end
+define private i32 @kitty_str_byte({ i8*, i32 } %str, i32 %i) {
+entry:
+  %len = extractvalue { i8*, i32 } %str, 1
+  %inside = icmp slt i32 %i, %len
+  br i1 %inside, label %load, label %end
+
+load:
+  %ptr = extractvalue { i8*, i32 } %str, 0
+  %at = getelementptr i8, i8* %ptr, i32 %i
+  %byte = load i8, i8* %at
+  %byte.wide = zext i8 %byte to i32
+  ret i32 %byte.wide
+
+end:
+  ret i32 -1
+}
+
+; leading whitespace and a sign are skipped, parsing stops at the first byte
+; that isn't a digit, and too many digits saturate before the cut to `i32`
+define i32 @kitty_str_to_int({ i8*, i32 } %str) {
+entry:
+  br label %space
+
+space:
+  %i.space = phi i32 [ 0, %entry ], [ %i.space.next, %space.next ]
+  %byte.space = call i32 @kitty_str_byte({ i8*, i32 } %str, i32 %i.space)
+  %blank = icmp eq i32 %byte.space, 32
+  %control = sub i32 %byte.space, 9
+  %tab = icmp ult i32 %control, 5
+  %skip = or i1 %blank, %tab
+  br i1 %skip, label %space.next, label %sign
+
+space.next:
+  %i.space.next = add i32 %i.space, 1
+  br label %space
+
+sign:
+  %minus = icmp eq i32 %byte.space, 45
+  %plus = icmp eq i32 %byte.space, 43
+  %signed = or i1 %minus, %plus
+  %skipped = zext i1 %signed to i32
+  %i.digits = add i32 %i.space, %skipped
+  br label %digit
+
+digit:
+  %i = phi i32 [ %i.digits, %sign ], [ %i.next, %accumulate ]
+  %int = phi i64 [ 0, %sign ], [ %int.next, %accumulate ]
+  %byte = call i32 @kitty_str_byte({ i8*, i32 } %str, i32 %i)
+  %value = sub i32 %byte, 48
+  %is.digit = icmp ult i32 %value, 10
+  br i1 %is.digit, label %accumulate, label %done
+
+accumulate:
+  %value.wide = zext i32 %value to i64
+  %room = sub i64 9223372036854775807, %value.wide
+  %limit = sdiv i64 %room, 10
+  %fits = icmp sle i64 %int, %limit
+  %int.shifted = mul i64 %int, 10
+  %int.added = add i64 %int.shifted, %value.wide
+  %int.next = select i1 %fits, i64 %int.added, i64 9223372036854775807
+  %i.next = add i32 %i, 1
+  br label %digit
+
+done:
+  %negated = sub i64 0, %int
+  %result.wide = select i1 %minus, i64 %negated, i64 %int
+  %result = trunc i64 %result.wide to i32
+  ret i32 %result
+}
+
+define { i8*, i32 } @kitty_int_to_str(i32 %int) {
+entry:
+  %int.wide = sext i32 %int to i64
+  %str = call { i8*, i32 } @kitty_format_int(i64 %int.wide, i32 10, i1 false, i1 false, i1 false, i32 0, i32 -1)
+  ret { i8*, i32 } %str
+}
+
+; lays out a new string like `printf` pads a field: `%zeros` zeros go between
+; the sign and the body, and the field is filled up to `%width` with spaces on
+; the left, on the right when `%left` is set, or with more zeros when `%zero`
+; is set
+define private { i8*, i32 } @kitty_pad(i8* %sign, i32 %sign.len, i32 %zeros, i8* %body, i32 %body.len, i1 %left, i1 %zero, i32 %width) {
+entry:
+  %prefix.len = add i32 %sign.len, %zeros
+  %content = add i32 %prefix.len, %body.len
+  %short = icmp slt i32 %content, %width
+  %missing = sub i32 %width, %content
+  %pad = select i1 %short, i32 %missing, i32 0
+  %len = add i32 %content, %pad
+  %len.wide = call %size_t @kitty_size(i32 %len)
+  %size = add %size_t %len.wide, 1
+  %buf = call i8* @kitty_alloc(%size_t %size)
+  %zeros.padded = add i32 %zeros, %pad
+  %zeros.all = select i1 %zero, i32 %zeros.padded, i32 %zeros
+  %spaces = select i1 %zero, i32 0, i32 %pad
+  %sign.at = select i1 %left, i32 0, i32 %spaces
+  %zeros.at = add i32 %sign.at, %sign.len
+  %body.at = add i32 %zeros.at, %zeros.all
+  %body.end = add i32 %body.at, %body.len
+  %spaces.at = select i1 %left, i32 %body.end, i32 0
+  %spaces.to = getelementptr i8, i8* %buf, i32 %spaces.at
+  %spaces.wide = call %size_t @kitty_size(i32 %spaces)
+  call void @kitty_mem_fill(i8* %spaces.to, i8 32, %size_t %spaces.wide)
+  %sign.to = getelementptr i8, i8* %buf, i32 %sign.at
+  %sign.wide = call %size_t @kitty_size(i32 %sign.len)
+  call void @kitty_mem_copy(i8* %sign.to, i8* %sign, %size_t %sign.wide)
+  %zeros.to = getelementptr i8, i8* %buf, i32 %zeros.at
+  %zeros.wide = call %size_t @kitty_size(i32 %zeros.all)
+  call void @kitty_mem_fill(i8* %zeros.to, i8 48, %size_t %zeros.wide)
+  %body.to = getelementptr i8, i8* %buf, i32 %body.at
+  %body.wide = call %size_t @kitty_size(i32 %body.len)
+  call void @kitty_mem_copy(i8* %body.to, i8* %body, %size_t %body.wide)
+  %end = getelementptr i8, i8* %buf, %size_t %len.wide
+  store i8 0, i8* %end
+  %str = call { i8*, i32 } @kitty_str_new(i8* %buf, i32 %len)
+  ret { i8*, i32 } %str
+}
+
+; only base 10 has a sign, the compiler zero extends the ints it prints in hex
+; or octal so these print their bits. A negative `%precision` is none, and the
+; field is only padded with zeros without one
+define { i8*, i32 } @kitty_format_int(i64 %int, i32 %base, i1 %upper, i1 %left, i1 %zero, i32 %width, i32 %precision) {
+entry:
+  %digits.buf = alloca [64 x i8]
+  %decimal = icmp eq i32 %base, 10
+  %below = icmp slt i64 %int, 0
+  %negative = and i1 %decimal, %below
+  %negated = sub i64 0, %int
+  %magnitude = select i1 %negative, i64 %negated, i64 %int
+  %base.wide = zext i32 %base to i64
+  %letters = select i1 %upper, i8 55, i8 87
+  br label %digit
+
+digit:
+  %rest = phi i64 [ %magnitude, %entry ], [ %rest.next, %digit ]
+  %at = phi i32 [ 64, %entry ], [ %at.next, %digit ]
+  %at.next = sub i32 %at, 1
+  %value.wide = urem i64 %rest, %base.wide
+  %rest.next = udiv i64 %rest, %base.wide
+  %value = trunc i64 %value.wide to i8
+  %letter = icmp uge i8 %value, 10
+  %offset = select i1 %letter, i8 %letters, i8 48
+  %char = add i8 %value, %offset
+  %slot = getelementptr [64 x i8], [64 x i8]* %digits.buf, i32 0, i32 %at.next
+  store i8 %char, i8* %slot
+  %more = icmp ne i64 %rest.next, 0
+  br i1 %more, label %digit, label %done
+
+done:
+  %digits = getelementptr [64 x i8], [64 x i8]* %digits.buf, i32 0, i32 %at.next
+  %digits.len = sub i32 64, %at.next
+  %short = icmp slt i32 %digits.len, %precision
+  %missing = sub i32 %precision, %digits.len
+  %zeros = select i1 %short, i32 %missing, i32 0
+  %sign = getelementptr [1 x i8], [1 x i8]* @kitty_minus, i64 0, i64 0
+  %sign.len = zext i1 %negative to i32
+  %unprecise = icmp slt i32 %precision, 0
+  %zero.pad = and i1 %zero, %unprecise
+  %str = call { i8*, i32 } @kitty_pad(i8* %sign, i32 %sign.len, i32 %zeros, i8* %digits, i32 %digits.len, i1 %left, i1 %zero.pad, i32 %width)
+  ret { i8*, i32 } %str
+}
+
+; a new copy of at most `%precision` bytes of the string, padded to `%width`
+define { i8*, i32 } @kitty_format_str({ i8*, i32 } %str, i1 %left, i32 %width, i32 %precision) {
+entry:
+  %ptr = extractvalue { i8*, i32 } %str, 0
+  %len = extractvalue { i8*, i32 } %str, 1
+  %precise = icmp sge i32 %precision, 0
+  %shorter = icmp slt i32 %precision, %len
+  %cut = and i1 %precise, %shorter
+  %body.len = select i1 %cut, i32 %precision, i32 %len
+  %sign = getelementptr [1 x i8], [1 x i8]* @kitty_minus, i64 0, i64 0
+  %padded = call { i8*, i32 } @kitty_pad(i8* %sign, i32 0, i32 0, i8* %ptr, i32 %body.len, i1 %left, i1 false, i32 %width)
+  ret { i8*, i32 } %padded
+}
+
+; wraps the arguments `main` was started with, the strings aren't copied
+define { { i8*, i32 }*, i32 } @kitty_args(i32 %argc, i8** %argv) {
+entry:
+  %argc.wide = call %size_t @kitty_size(i32 %argc)
+  %str.size.ptr = getelementptr { i8*, i32 }, { i8*, i32 }* null, i32 1
+  %str.size = ptrtoint { i8*, i32 }* %str.size.ptr to %size_t
+  %size = mul %size_t %str.size, %argc.wide
+  %buf = call i8* @kitty_alloc(%size_t %size)
+  %strs = bitcast i8* %buf to { i8*, i32 }*
+  br label %check
+
+check:
+  %i = phi i32 [ 0, %entry ], [ %next, %loop ]
+  %more = icmp slt i32 %i, %argc
+  br i1 %more, label %loop, label %done
+
+loop:
+  %arg.ptr = getelementptr i8*, i8** %argv, i32 %i
+  %arg = load i8*, i8** %arg.ptr
+  %str = call { i8*, i32 } @kitty_str_from_cstr(i8* %arg)
+  %slot = getelementptr { i8*, i32 }, { i8*, i32 }* %strs, i32 %i
+  store { i8*, i32 } %str, { i8*, i32 }* %slot
+  %next = add i32 %i, 1
+  br label %check
+
+done:
+  %list.0 = insertvalue { { i8*, i32 }*, i32 } undef, { i8*, i32 }* %strs, 0
+  %list = insertvalue { { i8*, i32 }*, i32 } %list.0, i32 %argc, 1
+  ret { { i8*, i32 }*, i32 } %list
+}
+
+define i32 @kitty_str_list_count({ { i8*, i32 }*, i32 } %list) {
+entry:
+  %count = extractvalue { { i8*, i32 }*, i32 } %list, 1
+  ret i32 %count
+}
+
+define { i8*, i32 } @kitty_str_list_at({ { i8*, i32 }*, i32 } %list, i32 %index) {
+entry:
+  %strs = extractvalue { { i8*, i32 }*, i32 } %list, 0
+  %count = extractvalue { { i8*, i32 }*, i32 } %list, 1
+  %under = icmp slt i32 %index, 0
+  %over = icmp sge i32 %index, %count
+  %outside = or i1 %under, %over
+  br i1 %outside, label %out_of_bounds, label %inside
+
+out_of_bounds:
+  %message.ptr = getelementptr [19 x i8], [19 x i8]* @kitty_out_of_bounds, i64 0, i64 0
+  %message = call { i8*, i32 } @kitty_str_new(i8* %message.ptr, i32 19)
+  call void @kitty_abort({ i8*, i32 } %message)
+  unreachable
+
+inside:
+  %slot = getelementptr { i8*, i32 }, { i8*, i32 }* %strs, i32 %index
+  %str = load { i8*, i32 }, { i8*, i32 }* %slot
+  ret { i8*, i32 } %str
+}
@@ -0,0 +1,176 @@
+use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
+use inkwell::module::Module;
+use inkwell::values::BasicValueEnum;
+use inkwell::values::PointerValue;
+
+use crate::lexer::Ident;
+use crate::lexer::Position;
+
+use super::compile::Compiler;
+use super::compile::State;
+
+const RUNTIME: &str = include_str!("runtime.ll");
+const HOSTED: &str = include_str!("runtime_hosted.ll");
+const FREESTANDING: &str = include_str!("runtime_freestanding.ll");
+
+// declared by the runtime and provided by the embedder on wasm targets
+pub(super) const IMPORTS: &[&str] = &["kitty_write"];
+
+const BUILTINS: &[(&str, RuntimeFn)] = &[
+    ("printf", RuntimeFn::Print),
+    ("abort", RuntimeFn::Abort),
+    ("panic", RuntimeFn::Panic),
+    ("len", RuntimeFn::StrLen),
+    ("concat", RuntimeFn::StrConcat),
+    ("compare", RuntimeFn::StrCompare),
+    ("slice", RuntimeFn::StrSlice),
+    ("parse_int", RuntimeFn::StrToInt),
+    ("to_str", RuntimeFn::IntToStr),
+    ("count", RuntimeFn::StrListCount),
+    ("at", RuntimeFn::StrListAt),
+];
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub(super) enum RuntimeFn {
+    Free,
+    Abort,
+    Panic,
+    Print,
+    StrToCstr,
+    StrLen,
+    StrConcat,
+    StrCompare,
+    StrSlice,
+    StrToInt,
+    IntToStr,
+    FormatInt,
+    FormatStr,
+    Args,
+    StrListCount,
+    StrListAt,
+}
+
+impl RuntimeFn {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            RuntimeFn::Free => "kitty_free",
+            RuntimeFn::Abort => "kitty_abort",
+            RuntimeFn::Panic => "kitty_panic",
+            RuntimeFn::Print => "kitty_print",
+            RuntimeFn::StrToCstr => "kitty_str_to_cstr",
+            RuntimeFn::StrLen => "kitty_str_len",
+            RuntimeFn::StrConcat => "kitty_str_concat",
+            RuntimeFn::StrCompare => "kitty_str_compare",
+            RuntimeFn::StrSlice => "kitty_str_slice",
+            RuntimeFn::StrToInt => "kitty_str_to_int",
+            RuntimeFn::IntToStr => "kitty_int_to_str",
+            RuntimeFn::FormatInt => "kitty_format_int",
+            RuntimeFn::FormatStr => "kitty_format_str",
+            RuntimeFn::Args => "kitty_args",
+            RuntimeFn::StrListCount => "kitty_str_list_count",
+            RuntimeFn::StrListAt => "kitty_str_list_at",
+        }
+    }
+}
+
+pub(super) fn load(ctx: &Context, ptr_bits: u64, hosted: bool) -> Module {
+    let platform = if hosted { HOSTED } else { FREESTANDING };
+
+    // the IR parser reads one byte past the end of its buffer and expects a NUL there
+    let source = format!("{}\n{}\n{}\0", prelude(ptr_bits), platform, RUNTIME);
+    let buffer =
+        MemoryBuffer::create_from_memory_range(&source.as_bytes()[..source.len() - 1], "runtime");
+
+    ctx.create_module_from_ir(buffer).unwrap() // the runtime ships with the compiler
+}
+
+// `size_t` is as wide as a pointer on every supported target, and `sext` and
+// `trunc` can't cast between types of the same width
+fn prelude(ptr_bits: u64) -> String {
+    let (widen, narrow) = if ptr_bits > 32 {
+        ("sext i32 %int to %size_t", "trunc %size_t %size to i32")
+    } else {
+        (
+            "bitcast i32 %int to %size_t",
+            "bitcast %size_t %size to i32",
+        )
+    };
+
+    format!(
+        "%size_t = type i{bits}
+
+define private %size_t @kitty_size(i32 %int) alwaysinline {{
+entry:
+  %size = {widen}
+  ret %size_t %size
+}}
+
+define private i32 @kitty_int(%size_t %size) alwaysinline {{
+entry:
+  %int = {narrow}
+  ret i32 %int
+}}
+",
+        bits = ptr_bits,
+        widen = widen,
+        narrow = narrow,
+    )
+}
+
+pub(super) fn builtin(name: &str) -> Option<RuntimeFn> {
+    BUILTINS
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, func)| *func)
+}
+
+impl<'c> Compiler<'c> {
+    pub(super) fn free(&self, state: &State, ptr: PointerValue) {
+        self.call_runtime(state, RuntimeFn::Free, &[ptr.into()]);
+    }
+
+    // only for strs the runtime allocated, that nothing else points into
+    pub(super) fn free_str(&self, state: &State, str: BasicValueEnum) {
+        let ptr = self
+            .call_runtime(state, RuntimeFn::StrToCstr, &[str])
+            .unwrap() // FIXME
+            .into_pointer_value();
+        self.free(state, ptr);
+    }
+
+    pub(super) fn panic(&self, state: &State, message: BasicValueEnum, pos: Position) {
+        let file = self.const_str("panic.file", &self.file);
+        let line = self.ctx.i32_type().const_int(pos.line as u64 + 1, false);
+        let col = self.ctx.i32_type().const_int(pos.col as u64 + 1, false);
+
+        self.call_runtime(
+            state,
+            RuntimeFn::Panic,
+            &[message, file, line.into(), col.into()],
+        );
+    }
+
+    pub(super) fn call_builtin(
+        &self,
+        state: &State,
+        name: &Ident,
+        args: &[BasicValueEnum],
+        pos: Position,
+    ) -> Option<BasicValueEnum> {
+        match builtin(name.inner()).unwrap() {
+            // FIXME
+            RuntimeFn::Abort => {
+                self.call_runtime(state, RuntimeFn::Abort, args);
+                self.unreachable(state);
+                None
+            }
+            RuntimeFn::Panic => {
+                self.panic(state, args[0], pos);
+                self.unreachable(state);
+                None
+            }
+            func => self.call_runtime(state, func, args),
+        }
+    }
+}
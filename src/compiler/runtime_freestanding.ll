@@ -0,0 +1,152 @@
+; kitty runtime: memory, output and failing on targets without a libc
+;
+; Nothing here needs a libc. Memory is bumped out of a static heap and never
+; reused, so freeing does nothing. The bytes are moved by hand and the
+; functions doing it are marked `no-builtins`, or the optimizer could turn the
+; loops back into calls to `memcpy` and friends.
+;
+; Output goes through `kitty_write`, the only function the embedder provides.
+; On wasm it's imported from the `env` module, or from the module passed to
+; `--wasm-import-module`. There is no stderr to report failures to, so these
+; only trap. A wasm runtime reports the trap to its embedder.
+
+declare void @llvm.trap() noreturn nounwind
+declare void @kitty_write(i8*, i32) "wasm-import-module"="env" "wasm-import-name"="kitty_write"
+
+@kitty_heap = internal global [65536 x i8] zeroinitializer, align 8
+@kitty_heap_top = internal global %size_t 0
+
+; sizes are rounded up to 8 bytes so every allocation stays aligned
+define private i8* @kitty_mem_alloc(%size_t %size) {
+entry:
+  %top = load %size_t, %size_t* @kitty_heap_top
+  %size.padded = add %size_t %size, 7
+  %size.aligned = and %size_t %size.padded, -8
+  %room = sub %size_t 65536, %top
+  %fits = icmp ule %size_t %size.aligned, %room
+  %wraps = icmp ult %size_t %size.padded, %size
+  %wraps.not = xor i1 %wraps, true
+  %ok = and i1 %fits, %wraps.not
+  br i1 %ok, label %bump, label %full
+
+bump:
+  %top.next = add %size_t %top, %size.aligned
+  store %size_t %top.next, %size_t* @kitty_heap_top
+  %ptr = getelementptr [65536 x i8], [65536 x i8]* @kitty_heap, i32 0, %size_t %top
+  ret i8* %ptr
+
+full:
+  ret i8* null
+}
+
+define void @kitty_free(i8* %ptr) {
+entry:
+  ret void
+}
+
+define private void @kitty_mem_copy(i8* %dest, i8* %src, %size_t %size) "no-builtins" {
+entry:
+  br label %check
+
+check:
+  %i = phi %size_t [ 0, %entry ], [ %next, %loop ]
+  %more = icmp ult %size_t %i, %size
+  br i1 %more, label %loop, label %done
+
+loop:
+  %from = getelementptr i8, i8* %src, %size_t %i
+  %byte = load i8, i8* %from
+  %to = getelementptr i8, i8* %dest, %size_t %i
+  store i8 %byte, i8* %to
+  %next = add %size_t %i, 1
+  br label %check
+
+done:
+  ret void
+}
+
+define private void @kitty_mem_fill(i8* %dest, i8 %byte, %size_t %size) "no-builtins" {
+entry:
+  br label %check
+
+check:
+  %i = phi %size_t [ 0, %entry ], [ %next, %loop ]
+  %more = icmp ult %size_t %i, %size
+  br i1 %more, label %loop, label %done
+
+loop:
+  %to = getelementptr i8, i8* %dest, %size_t %i
+  store i8 %byte, i8* %to
+  %next = add %size_t %i, 1
+  br label %check
+
+done:
+  ret void
+}
+
+define private i32 @kitty_mem_compare(i8* %left, i8* %right, %size_t %size) "no-builtins" {
+entry:
+  br label %check
+
+check:
+  %i = phi %size_t [ 0, %entry ], [ %next, %same ]
+  %more = icmp ult %size_t %i, %size
+  br i1 %more, label %loop, label %equal
+
+loop:
+  %left.at = getelementptr i8, i8* %left, %size_t %i
+  %right.at = getelementptr i8, i8* %right, %size_t %i
+  %left.byte = load i8, i8* %left.at
+  %right.byte = load i8, i8* %right.at
+  %eq = icmp eq i8 %left.byte, %right.byte
+  br i1 %eq, label %same, label %differ
+
+same:
+  %next = add %size_t %i, 1
+  br label %check
+
+differ:
+  %left.wide = zext i8 %left.byte to i32
+  %right.wide = zext i8 %right.byte to i32
+  %cmp = sub i32 %left.wide, %right.wide
+  ret i32 %cmp
+
+equal:
+  ret i32 0
+}
+
+define private %size_t @kitty_cstr_len(i8* %ptr) "no-builtins" {
+entry:
+  br label %loop
+
+loop:
+  %len = phi %size_t [ 0, %entry ], [ %next, %loop ]
+  %at = getelementptr i8, i8* %ptr, %size_t %len
+  %byte = load i8, i8* %at
+  %next = add %size_t %len, 1
+  %end = icmp eq i8 %byte, 0
+  br i1 %end, label %done, label %loop
+
+done:
+  ret %size_t %len
+}
+
+define void @kitty_print({ i8*, i32 } %str) {
+entry:
+  %ptr = extractvalue { i8*, i32 } %str, 0
+  %len = extractvalue { i8*, i32 } %str, 1
+  call void @kitty_write(i8* %ptr, i32 %len)
+  ret void
+}
+
+define void @kitty_abort({ i8*, i32 } %message) noreturn {
+entry:
+  call void @llvm.trap()
+  unreachable
+}
+
+define void @kitty_panic({ i8*, i32 } %message, { i8*, i32 } %file, i32 %line, i32 %col) noreturn {
+entry:
+  call void @llvm.trap()
+  unreachable
+}
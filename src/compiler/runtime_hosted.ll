@@ -0,0 +1,91 @@
+; kitty runtime: memory, output and failing on targets with a libc
+;
+; Memory comes from `malloc`. Failing writes the message to stderr before
+; aborting.
+
+declare i8* @malloc(%size_t)
+declare void @free(i8*)
+declare i8* @memcpy(i8*, i8*, %size_t)
+declare i8* @memset(i8*, i32, %size_t)
+declare i32 @memcmp(i8*, i8*, %size_t)
+declare %size_t @strlen(i8*)
+declare void @abort() noreturn
+declare i32 @printf(i8*, ...)
+declare i32 @dprintf(i32, i8*, ...)
+declare i32 @fflush(i8*)
+
+@kitty_print_format = private unnamed_addr constant [5 x i8] c"%.*s\00"
+@kitty_abort_format = private unnamed_addr constant [13 x i8] c"fatal: %.*s\0A\00"
+@kitty_panic_format = private unnamed_addr constant [30 x i8] c"panicked at %.*s:%d:%d: %.*s\0A\00"
+
+define private i8* @kitty_mem_alloc(%size_t %size) {
+entry:
+  %ptr = call i8* @malloc(%size_t %size)
+  ret i8* %ptr
+}
+
+define void @kitty_free(i8* %ptr) {
+entry:
+  call void @free(i8* %ptr)
+  ret void
+}
+
+define private void @kitty_mem_copy(i8* %dest, i8* %src, %size_t %size) {
+entry:
+  call i8* @memcpy(i8* %dest, i8* %src, %size_t %size)
+  ret void
+}
+
+define private void @kitty_mem_fill(i8* %dest, i8 %byte, %size_t %size) {
+entry:
+  %byte.wide = zext i8 %byte to i32
+  call i8* @memset(i8* %dest, i32 %byte.wide, %size_t %size)
+  ret void
+}
+
+define private i32 @kitty_mem_compare(i8* %left, i8* %right, %size_t %size) {
+entry:
+  %cmp = call i32 @memcmp(i8* %left, i8* %right, %size_t %size)
+  ret i32 %cmp
+}
+
+define private %size_t @kitty_cstr_len(i8* %ptr) {
+entry:
+  %len = call %size_t @strlen(i8* %ptr)
+  ret %size_t %len
+}
+
+; the string is written as is, it's never read as a format
+define void @kitty_print({ i8*, i32 } %str) {
+entry:
+  %ptr = extractvalue { i8*, i32 } %str, 0
+  %len = extractvalue { i8*, i32 } %str, 1
+  %format = getelementptr [5 x i8], [5 x i8]* @kitty_print_format, i64 0, i64 0
+  call i32 (i8*, ...) @printf(i8* %format, i32 %len, i8* %ptr)
+  ret void
+}
+
+; buffered output is flushed before writing to stderr, `abort` would drop it
+define void @kitty_abort({ i8*, i32 } %message) noreturn {
+entry:
+  call i32 @fflush(i8* null)
+  %ptr = extractvalue { i8*, i32 } %message, 0
+  %len = extractvalue { i8*, i32 } %message, 1
+  %format = getelementptr [13 x i8], [13 x i8]* @kitty_abort_format, i64 0, i64 0
+  call i32 (i32, i8*, ...) @dprintf(i32 2, i8* %format, i32 %len, i8* %ptr)
+  call void @abort()
+  unreachable
+}
+
+define void @kitty_panic({ i8*, i32 } %message, { i8*, i32 } %file, i32 %line, i32 %col) noreturn {
+entry:
+  call i32 @fflush(i8* null)
+  %ptr = extractvalue { i8*, i32 } %message, 0
+  %len = extractvalue { i8*, i32 } %message, 1
+  %file.ptr = extractvalue { i8*, i32 } %file, 0
+  %file.len = extractvalue { i8*, i32 } %file, 1
+  %format = getelementptr [30 x i8], [30 x i8]* @kitty_panic_format, i64 0, i64 0
+  call i32 (i32, i8*, ...) @dprintf(i32 2, i8* %format, i32 %file.len, i8* %file.ptr, i32 %line, i32 %col, i32 %len, i8* %ptr)
+  call void @abort()
+  unreachable
+}
@@ -1,7 +1,7 @@
+use inkwell::values::BasicValue;
+
 use crate::ast::Stmt;
-use crate::parser::expr::DynStringSeg;
 use crate::parser::expr::Expr;
-use crate::parser::expr::Literal;
 use crate::parser::stmt::Let;
 use crate::parser::stmt::Return;
 
@@ -10,10 +10,14 @@ use super::compile::State;
 use super::error::*;
 
 impl<'s> Stmt<'s> {
-    pub(super) fn prepare(&'s self, compiler: &mut Compiler<'s>, state: &mut State<'s>) {
+    pub(super) fn prepare(
+        &'s self,
+        compiler: &mut Compiler<'s>,
+        state: &mut State<'s>,
+    ) -> Result<()> {
         match self {
             Stmt::Let { let_, .. } => let_.prepare(compiler, state),
-            Stmt::Return { .. } => (),
+            Stmt::Return { .. } => Ok(()),
             Stmt::Expr { expr, .. } => expr.prepare(compiler, state),
         }
     }
@@ -32,62 +36,40 @@ impl<'s> Stmt<'s> {
 }
 
 impl<'l> Let<'l> {
-    pub(super) fn prepare(&'l self, compiler: &mut Compiler<'l>, state: &mut State<'l>) {
+    pub(super) fn prepare(
+        &'l self,
+        compiler: &mut Compiler<'l>,
+        state: &mut State<'l>,
+    ) -> Result<()> {
         match &self.value {
             Expr::Literal(lit) => {
-                match lit {
-                    Literal::RefDynString { segs, .. } => {
-                        let mut group = vec![lit.name()];
-                        for seg in *segs {
-                            if let DynStringSeg::Expr(expr) = seg {
-                                match expr {
-                                    Expr::Literal(lit) => group.push(lit.name()),
-                                    Expr::Func(_) => unimplemented!(), // FIXME
-                                    Expr::Var(var) => group.push(var.as_ref()),
-                                }
-                            }
-                        }
-
-                        compiler.register_var_group(state, self.name.as_ref(), group);
-                    }
-                    Literal::OwnedDynString { segs, .. } => {
-                        let mut group = vec![lit.name()];
-                        for seg in segs {
-                            if let DynStringSeg::Expr(expr) = seg {
-                                match expr {
-                                    Expr::Literal(lit) => group.push(lit.name()),
-                                    Expr::Func(_) => unimplemented!(), // FIXME
-                                    Expr::Var(var) => group.push(var.as_ref()),
-                                }
-                            }
-                        }
-
-                        compiler.register_var_group(state, self.name.as_ref(), group);
-                    }
-                    _ => {
-                        compiler.alias(state, self.name.as_ref(), lit.name());
-                    }
-                }
-
-                lit.prepare(compiler, state);
-            }
-            Expr::Func(func) => {
-                compiler.register_var(
-                    state,
-                    self.name.as_ref(),
-                    func.call(compiler, state).unwrap(), // FIXME
-                );
+                compiler.alias(state, self.name.as_ref(), lit.name());
+                lit.prepare(compiler, state)
             }
-            _ => unimplemented!(), // FIXME
+            Expr::Func(func) => func.prepare(compiler, state),
+            Expr::Var(_) => Ok(()),
         }
     }
 
-    pub(super) fn compile(&self, _: &mut Compiler<'l>, _: &mut State<'l>) -> Result<()> {
+    pub(super) fn compile(
+        &'l self,
+        compiler: &mut Compiler<'l>,
+        state: &mut State<'l>,
+    ) -> Result<()> {
         match &self.value {
-            Expr::Literal(_) => Ok(()),
-            Expr::Func(_) => Ok(()),
-            _ => unimplemented!(), // FIXME
+            Expr::Literal(_) => (),
+            Expr::Func(func) => {
+                let value = func.value(compiler, state)?;
+                compiler.register_var(state, self.name.as_ref(), value);
+            }
+            Expr::Var(var) => {
+                // the value is copied, so shadowing `var` later doesn't change this binding
+                let value = compiler.var(state, var)?[0];
+                compiler.register_var(state, self.name.as_ref(), value);
+            }
         }
+
+        Ok(())
     }
 }
 
@@ -97,17 +79,15 @@ impl<'r> Return<'r> {
         compiler: &mut Compiler<'r>,
         state: &mut State<'r>,
     ) -> Result<()> {
-        match &self.0 {
-            Expr::Literal(lit) => {
-                lit.prepare(compiler, state);
-                compiler.ret(
-                    state,
-                    Some(&compiler.get_var(state, &lit.name()).unwrap()[0]), // FIXME
-                );
+        let value = match &self.0 {
+            Expr::Func(func) => {
+                func.prepare(compiler, state)?;
+                func.call(compiler, state)?
             }
-            _ => unimplemented!(), // FIXME
-        }
+            expr => Some(expr.value(compiler, state)?),
+        };
 
+        compiler.ret(state, value.as_ref().map(|value| value as &dyn BasicValue));
         Ok(())
     }
 }
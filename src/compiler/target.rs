@@ -0,0 +1,178 @@
+use std::ffi::CStr;
+use std::ffi::CString;
+
+use inkwell::targets::CodeModel;
+use inkwell::targets::InitializationConfig;
+use inkwell::targets::RelocMode;
+use inkwell::targets::Target;
+use inkwell::targets::TargetMachine;
+use llvm_sys::core::LLVMDisposeMessage;
+use llvm_sys::target_machine::LLVMGetHostCPUName;
+use llvm_sys::target_machine::LLVMNormalizeTargetTriple;
+
+use super::error::*;
+use super::opt::OptLevel;
+
+type Init = fn(&InitializationConfig);
+
+const ARCHS: &[(&str, Init)] = &[
+    ("x86_64", Target::initialize_x86),
+    ("i386", Target::initialize_x86),
+    ("i686", Target::initialize_x86),
+    ("aarch64", Target::initialize_aarch64),
+    ("arm64", Target::initialize_aarch64),
+    ("arm", Target::initialize_arm),
+    ("thumb", Target::initialize_arm),
+    ("riscv32", Target::initialize_riscv),
+    ("riscv64", Target::initialize_riscv),
+    ("wasm32", Target::initialize_webassembly),
+    ("wasm64", Target::initialize_webassembly),
+];
+
+#[derive(Clone, Debug)]
+pub struct TargetSpec {
+    pub(super) triple: String,
+    pub(super) cpu: String,
+    pub(super) features: String,
+}
+
+impl TargetSpec {
+    pub fn new(triple: Option<&str>, cpu: Option<&str>, features: &[String]) -> Result<TargetSpec> {
+        let (triple, cpu) = match (triple, cpu) {
+            (Some(triple), cpu) => (triple.to_string(), cpu.unwrap_or("").to_string()),
+            (None, Some(cpu)) => (host_triple(), cpu.to_string()),
+            (None, None) => (host_triple(), host_cpu()),
+        };
+        let triple = triple.as_str();
+
+        let arch = triple.split('-').next().unwrap_or("");
+        let init = ARCHS
+            .iter()
+            .find(|(name, _)| arch.starts_with(name))
+            .map(|(_, init)| init)
+            .ok_or_else(|| Error::unsupported_target(triple.into()))?;
+
+        init(&InitializationConfig::default());
+
+        if Target::from_triple(triple).is_err() {
+            return Err(Error::unsupported_target(triple.into()));
+        }
+
+        let features = features
+            .iter()
+            .map(|feature| {
+                if feature.starts_with('+') || feature.starts_with('-') {
+                    feature.clone()
+                } else {
+                    format!("+{}", feature)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Ok(TargetSpec {
+            triple: triple.into(),
+            cpu,
+            features,
+        })
+    }
+
+    pub fn triple(&self) -> &str {
+        &self.triple
+    }
+
+    // targets without an OS, like wasm32-unknown-unknown, have no libc. The
+    // vendor is optional, so the OS is only third once LLVM fills it in
+    pub(super) fn hosted(&self) -> bool {
+        !matches!(
+            normalize_triple(&self.triple).split('-').nth(2),
+            Some("unknown") | Some("none") | None
+        )
+    }
+
+    pub(super) fn target_machine(&self, level: OptLevel) -> Result<TargetMachine> {
+        let target = Target::from_triple(&self.triple).map_err(Error::llvm)?;
+        target
+            .create_target_machine(
+                &self.triple,
+                &self.cpu,
+                &self.features,
+                level.llvm(),
+                RelocMode::Default,
+                CodeModel::Default,
+            )
+            .ok_or_else(|| Error::unsupported_target(self.triple.clone()))
+    }
+}
+
+pub(super) fn supported_archs() -> Vec<&'static str> {
+    ARCHS.iter().map(|(name, _)| *name).collect()
+}
+
+fn host_triple() -> String {
+    TargetMachine::get_default_triple().to_string()
+}
+
+// `thumbv7em-none-eabi` becomes `thumbv7em-none-unknown-eabi`
+fn normalize_triple(triple: &str) -> String {
+    let triple = match CString::new(triple) {
+        Ok(triple) => triple,
+        Err(_) => return String::new(), // no OS to find in a triple with a NUL
+    };
+
+    unsafe {
+        let normalized = LLVMNormalizeTargetTriple(triple.as_ptr());
+        let string = CStr::from_ptr(normalized).to_string_lossy().into_owned();
+        LLVMDisposeMessage(normalized);
+
+        string
+    }
+}
+
+fn host_cpu() -> String {
+    unsafe {
+        let name = LLVMGetHostCPUName();
+        let cpu = CStr::from_ptr(name).to_string_lossy().into_owned();
+        LLVMDisposeMessage(name);
+
+        cpu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(triple: &str) -> TargetSpec {
+        TargetSpec {
+            triple: triple.into(),
+            cpu: String::new(),
+            features: String::new(),
+        }
+    }
+
+    #[test]
+    fn only_targets_with_an_os_are_hosted() {
+        for triple in &[
+            "x86_64-unknown-linux-gnu",
+            "x86_64-linux-gnu",
+            "aarch64-apple-darwin",
+            "wasm32-wasi",
+        ] {
+            assert!(spec(triple).hosted(), "{}", triple);
+        }
+
+        for triple in &[
+            "wasm32-unknown-unknown",
+            "thumbv7em-none-eabi",
+            "riscv32-unknown-none-elf",
+        ] {
+            assert!(!spec(triple).hosted(), "{}", triple);
+        }
+    }
+
+    #[test]
+    fn arm64_is_aarch64() {
+        assert!(TargetSpec::new(Some("arm64-apple-macosx"), None, &[]).is_ok());
+    }
+}
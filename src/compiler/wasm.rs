@@ -0,0 +1,19 @@
+use inkwell::attributes::AttributeLoc;
+
+use super::compile::Compiled;
+use super::runtime;
+
+impl Compiled {
+    // only what the runtime expects from the embedder is imported, the other
+    // declarations are resolved when linking
+    pub fn set_import_module(&self, name: &str) {
+        let ctx = self.runtime.get_context();
+        let attr = ctx.create_string_attribute("wasm-import-module", name);
+
+        for name in runtime::IMPORTS {
+            if let Some(func) = self.runtime.get_function(name) {
+                func.add_attribute(AttributeLoc::Function, attr);
+            }
+        }
+    }
+}
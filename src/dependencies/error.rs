@@ -3,6 +3,7 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 
 use crate::lexer::Ident;
+use crate::lexer::Position;
 
 use super::graph::Dependency;
 
@@ -29,6 +30,21 @@ impl<'e> Error<'e> {
         }
     }
 
+    pub fn pos(&self) -> Option<Position> {
+        match &self.kind {
+            ErrorKind::MissingDependency { dependency, .. } => Some(dependency.pos()),
+            ErrorKind::Multiple(_) => None,
+        }
+    }
+
+    pub fn errors(&self) -> Vec<&Error<'e>> {
+        if let ErrorKind::Multiple(errors) = &self.kind {
+            errors.iter().flat_map(|error| error.errors()).collect()
+        } else {
+            vec![self]
+        }
+    }
+
     pub(super) fn multiple(errors: Vec<Error<'e>>) -> Error<'e> {
         Error {
             kind: ErrorKind::Multiple(errors),
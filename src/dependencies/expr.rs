@@ -1,13 +1,15 @@
+use crate::parser::expr::DynStringSeg;
 use crate::parser::expr::Expr;
+use crate::parser::expr::Literal;
 
 use super::graph::Dependency;
 
 impl<'e> Expr<'e> {
-    pub(super) fn dependencies(&self) -> Vec<Dependency> {
+    pub(super) fn dependencies(&self) -> Vec<Dependency<'_>> {
         match self {
-            Expr::Literal(_) => vec![],
+            Expr::Literal(lit) => lit.dependencies(),
             Expr::Func(func) => {
-                let mut dependencies = vec![Dependency::Func(func.name.as_ref())];
+                let mut dependencies = vec![Dependency::Func(func.name.as_ref(), func.pos)];
 
                 for arg in func.args.inner() {
                     dependencies.append(&mut arg.dependencies());
@@ -19,3 +21,22 @@ impl<'e> Expr<'e> {
         }
     }
 }
+
+impl<'l> Literal<'l> {
+    fn dependencies(&self) -> Vec<Dependency<'_>> {
+        let segs: &[DynStringSeg] = match self {
+            Literal::RefDynString { segs, .. } => segs,
+            Literal::OwnedDynString { segs, .. } => segs,
+            _ => return vec![],
+        };
+
+        let mut dependencies = vec![];
+        for seg in segs {
+            if let DynStringSeg::Expr(expr, _) = seg {
+                dependencies.append(&mut expr.dependencies());
+            }
+        }
+
+        dependencies
+    }
+}
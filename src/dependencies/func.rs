@@ -37,13 +37,13 @@ impl<'f> Func<'f> {
         }
     }
 
-    pub(super) fn verify(&self, graph: &Graph) -> Result<()> {
+    pub(super) fn verify(&self, graph: &Graph) -> Result<'_, ()> {
         let mut error = Error::multiple(vec![]);
 
         let module = graph.modules.get(&self.module).unwrap();
         for dependency in &self.dependencies {
             match dependency {
-                Dependency::Func(func) => {
+                Dependency::Func(func, _) => {
                     if !module.funcs.contains_key(func) {
                         error = error.concat(Error::missing_dependency(
                             self.name.as_ref(),
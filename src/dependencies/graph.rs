@@ -1,10 +1,13 @@
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::hash::Hash;
+use std::hash::Hasher;
 
 use fnv::FnvHashMap;
 
 use crate::lexer::Ident;
+use crate::lexer::Position;
 
 use super::module::Module;
 
@@ -13,15 +16,39 @@ pub struct Graph<'g> {
     pub(super) modules: FnvHashMap<Ident<'g>, Module<'g>>,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Debug)]
 pub enum Dependency<'d> {
-    Func(Ident<'d>),
+    Func(Ident<'d>, Position),
 }
 
 impl<'d> Dependency<'d> {
     pub fn as_ref(&'d self) -> Dependency<'d> {
         match self {
-            Dependency::Func(func) => Dependency::Func(func.as_ref()),
+            Dependency::Func(func, pos) => Dependency::Func(func.as_ref(), *pos),
+        }
+    }
+
+    pub fn pos(&self) -> Position {
+        match self {
+            Dependency::Func(_, pos) => *pos,
+        }
+    }
+}
+
+impl<'d> Hash for Dependency<'d> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Dependency::Func(func, _) => func.hash(state),
+        }
+    }
+}
+
+impl<'d> Eq for Dependency<'d> {}
+
+impl<'d> PartialEq for Dependency<'d> {
+    fn eq(&self, other: &Dependency<'d>) -> bool {
+        match (self, other) {
+            (Dependency::Func(func, _), Dependency::Func(other, _)) => func == other,
         }
     }
 }
@@ -51,7 +78,7 @@ impl<'g> Display for Graph<'g> {
 impl<'d> Display for Dependency<'d> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match self {
-            Dependency::Func(func) => write!(fmt, "func({})", func.inner()),
+            Dependency::Func(func, _) => write!(fmt, "func({})", func.inner()),
         }
     }
 }
@@ -10,7 +10,6 @@ mod module;
 mod stmt;
 
 pub use error::*;
-pub use graph::Dependency;
 pub use graph::Graph;
 
 use module::Module;
@@ -30,7 +29,7 @@ impl<'g> Graph<'g> {
         graph
     }
 
-    pub fn verify(&self) -> Result<()> {
+    pub fn verify(&self) -> Result<'_, ()> {
         let mut error = Error::multiple(vec![]);
 
         for module in self.modules.values() {
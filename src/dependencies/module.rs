@@ -10,7 +10,6 @@ use super::graph::Graph;
 
 #[derive(Debug)]
 pub(super) struct Module<'m> {
-    pub(super) name: Ident<'m>,
     pub(super) funcs: FnvHashMap<Ident<'m>, Func<'m>>,
 }
 
@@ -19,7 +18,6 @@ impl<'m> Module<'m> {
         let name = module.name.as_ref();
         let funcs = &module.funcs;
         let mut module = Module {
-            name: module.name.as_ref(),
             funcs: FnvHashMap::default(),
         };
 
@@ -37,7 +35,7 @@ impl<'m> Module<'m> {
         module
     }
 
-    pub(super) fn verify(&self, graph: &Graph) -> Result<()> {
+    pub(super) fn verify(&self, graph: &Graph) -> Result<'_, ()> {
         let mut error = Error::multiple(vec![]);
 
         for func in self.funcs.values() {
@@ -3,7 +3,7 @@ use crate::ast::Stmt;
 use super::graph::Dependency;
 
 impl<'s> Stmt<'s> {
-    pub(super) fn dependencies(&self) -> Vec<Dependency> {
+    pub(super) fn dependencies(&self) -> Vec<Dependency<'_>> {
         match self {
             Stmt::Let { let_, .. } => let_.value.dependencies(),
             Stmt::Return { ret, .. } => ret.0.dependencies(),
@@ -0,0 +1,59 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::lexer;
+use crate::parser;
+
+pub type Result<OK> = std::result::Result<OK, Error>;
+
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    Lex(lexer::Error),
+    Parse(String),
+}
+
+impl Error {
+    pub(super) fn lex(err: lexer::Error) -> Error {
+        Error {
+            kind: ErrorKind::Lex(err),
+        }
+    }
+
+    pub(super) fn parse(err: &parser::Error) -> Error {
+        Error {
+            kind: ErrorKind::Parse(err.to_string()),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Lex(err) => {
+                for (e, err) in err.errors().iter().enumerate() {
+                    if e > 0 {
+                        write!(fmt, ", ")?;
+                    }
+
+                    let pos = err.pos().unwrap(); // `errors` flattens `Multiple`
+                    write!(
+                        fmt,
+                        "lexer error at {}:{}: {}",
+                        pos.line + 1,
+                        pos.col + 1,
+                        err
+                    )?;
+                }
+
+                Ok(())
+            }
+            ErrorKind::Parse(err) => write!(fmt, "parser error: {}", err),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::lexer::lex;
+use crate::lexer::lex_trivia;
+use crate::parser::parse;
+
+mod error;
+mod printer;
+
+pub use error::*;
+
+use printer::Printer;
+
+pub fn format(input: &str) -> Result<String> {
+    let tokens = lex(input).map_err(Error::lex)?;
+    if let Err(err) = parse(&tokens) {
+        return Err(Error::parse(&err));
+    }
+
+    let mut printer = Printer::new();
+    for token in &lex_trivia(input) {
+        printer.token(token);
+    }
+
+    Ok(printer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    fn idempotent(input: &str) -> String {
+        let output = format(input).unwrap();
+        assert_eq!(format(&output).unwrap(), output);
+
+        output
+    }
+
+    // the only examples that don't lex or parse
+    const BROKEN_EXAMPLES: &[&str] = &["lexer_errors.kt"];
+
+    #[test]
+    fn is_idempotent_on_examples() {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+
+        let mut broken = vec![];
+        for entry in fs::read_dir(examples).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "kt") {
+                continue;
+            }
+
+            let name = path.file_name().unwrap().to_str().unwrap();
+            let input = fs::read_to_string(&path).unwrap();
+            if BROKEN_EXAMPLES.contains(&name) {
+                assert!(format(&input).is_err(), "{}", path.display());
+                broken.push(name.to_string());
+                continue;
+            }
+
+            let output = format(&input).unwrap();
+            assert_eq!(format(&output).unwrap(), output, "{}", path.display());
+        }
+
+        assert_eq!(broken, BROKEN_EXAMPLES);
+    }
+
+    #[test]
+    fn formats_comments_and_blank_lines() {
+        let input = "/* a */ func  one(){\n\n\n  one( );// b\n}\n/* /* c */ */\nfunc two(\n){}";
+
+        assert_eq!(
+            idempotent(input),
+            "/* a */ func one() {\n\tone(); // b\n}\n\n/* /* c */ */\nfunc two() {}\n"
+        );
+    }
+
+    #[test]
+    fn formats_dyn_strings_by_segment() {
+        let input = "func main() { printf(`http://${ f( 1 ,2 ):04x}/*${ `${a}` }`); }";
+
+        assert_eq!(
+            idempotent(input),
+            "func main() {\n\tprintf(`http://${f(1, 2):04x}/*${`${a}`}`);\n}\n"
+        );
+    }
+
+    #[test]
+    fn keeps_comments_in_inserts() {
+        let input = "func main() { printf(`${ /* a */ f( 1 ) // b\n }`); }";
+
+        assert_eq!(
+            idempotent(input),
+            "func main() {\n\tprintf(`${/* a */ f(1) // b\n}`);\n}\n"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use crate::lexer::lex_trivia;
+use crate::lexer::DynStringSeg;
+use crate::lexer::Literal;
+use crate::lexer::Symbol;
+use crate::lexer::TokenVariant;
+use crate::lexer::Trivia;
+use crate::lexer::TriviaToken;
+
+#[derive(Debug)]
+pub(super) struct Printer {
+    out: String,
+    depth: usize,
+    line_start: bool,
+    break_pending: bool,
+    newlines: usize,
+    last: Last,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+enum Last {
+    Nothing,
+    Comment,
+    Symbol(Symbol),
+    Other,
+}
+
+impl Printer {
+    pub(super) fn new() -> Printer {
+        Printer {
+            out: String::new(),
+            depth: 0,
+            line_start: true,
+            break_pending: false,
+            newlines: 0,
+            last: Last::Nothing,
+        }
+    }
+
+    pub(super) fn token(&mut self, token: &TriviaToken) {
+        for trivia in &token.leading {
+            match trivia {
+                Trivia::Whitespace(whitespace) => self.newlines += whitespace.matches('\n').count(),
+                Trivia::Comment(comment) => self.comment(comment),
+            }
+        }
+
+        match &token.token.token {
+            TokenVariant::Eof => {
+                self.newline();
+                return;
+            }
+            TokenVariant::Symbol(Symbol::RightBrace) => {
+                self.depth -= 1;
+
+                if self.last == Last::Symbol(Symbol::LeftBrace) {
+                    self.break_pending = false;
+                    self.write("}", false);
+                } else {
+                    self.start(true);
+                    self.write("}", false);
+                }
+
+                self.break_pending = true;
+            }
+            variant => {
+                if self.break_pending || (self.last == Last::Comment && self.newlines > 0) {
+                    self.start(false);
+                }
+
+                let text = match variant {
+                    TokenVariant::Literal(Literal::DynString(segs)) => dyn_string(segs),
+                    _ => token.text.to_string(),
+                };
+
+                let space = space(self.last, variant);
+                self.write(&text, space);
+
+                match variant {
+                    TokenVariant::Symbol(Symbol::LeftBrace) => {
+                        self.depth += 1;
+                        self.break_pending = true;
+                    }
+                    TokenVariant::Symbol(Symbol::SemiColon) => self.break_pending = true,
+                    _ => (),
+                }
+            }
+        }
+
+        self.last = Last::from(&token.token.token);
+        self.newlines = 0;
+
+        for trivia in &token.trailing {
+            match trivia {
+                Trivia::Whitespace(whitespace) => self.newlines += whitespace.matches('\n').count(),
+                Trivia::Comment(comment) => {
+                    self.write(comment.trim_end(), true);
+                    self.last = Last::Comment;
+
+                    if comment.starts_with("//") {
+                        self.break_pending = true;
+                        self.newlines += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    pub(super) fn finish(self) -> String {
+        self.out
+    }
+
+    fn comment(&mut self, comment: &str) {
+        if self.break_pending || self.newlines > 0 {
+            self.start(false);
+        }
+
+        self.write(comment.trim_end(), true);
+        self.last = Last::Comment;
+
+        if comment.starts_with("//") {
+            self.break_pending = true;
+            self.newlines = 1;
+        } else {
+            self.newlines = 0;
+        }
+    }
+
+    fn start(&mut self, closing: bool) {
+        let blank = self.newlines >= 2 && !closing && self.last != Last::Symbol(Symbol::LeftBrace);
+        let decl = self.depth == 0 && self.last == Last::Symbol(Symbol::RightBrace);
+
+        if (blank || decl) && !self.out.is_empty() {
+            self.newline();
+            self.out.push('\n');
+        } else {
+            self.newline();
+        }
+
+        self.break_pending = false;
+        self.newlines = 0;
+    }
+
+    fn newline(&mut self) {
+        if !self.line_start {
+            self.out.push('\n');
+            self.line_start = true;
+        }
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.line_start {
+            for _ in 0..self.depth {
+                self.out.push('\t');
+            }
+
+            self.line_start = false;
+        } else if space {
+            self.out.push(' ');
+        }
+
+        self.out.push_str(text);
+    }
+}
+
+impl<'t> From<&TokenVariant<'t>> for Last {
+    fn from(token: &TokenVariant<'t>) -> Last {
+        match token {
+            TokenVariant::Symbol(symbol) => Last::Symbol(*symbol),
+            _ => Last::Other,
+        }
+    }
+}
+
+fn space(last: Last, token: &TokenVariant) -> bool {
+    !matches!(
+        (last, token),
+        (Last::Nothing, _)
+            | (_, TokenVariant::Symbol(Symbol::LeftParen))
+            | (_, TokenVariant::Symbol(Symbol::RightParen))
+            | (_, TokenVariant::Symbol(Symbol::Comma))
+            | (_, TokenVariant::Symbol(Symbol::Colon))
+            | (_, TokenVariant::Symbol(Symbol::SemiColon))
+            | (Last::Symbol(Symbol::LeftParen), _)
+    )
+}
+
+fn dyn_string(segs: &[DynStringSeg]) -> String {
+    let mut string = String::from("`");
+
+    for seg in segs {
+        match seg {
+            DynStringSeg::String(string_) => string.push_str(string_),
+            DynStringSeg::Insert(_, spec, source) => {
+                string.push_str("${");
+                string.push_str(&insert(source));
+
+                if let Some(spec) = spec {
+                    string.push_str(&format!(":{}", spec));
+                }
+
+                string.push('}');
+            }
+        }
+    }
+
+    string.push('`');
+    string
+}
+
+// inserts are lexed again with their trivia, so that comments in them are kept
+fn insert(source: &str) -> String {
+    let mut string = String::new();
+    let mut last = Last::Nothing;
+
+    for token in &lex_trivia(source) {
+        for trivia in &token.leading {
+            insert_trivia(&mut string, trivia, &mut last);
+        }
+
+        if !token.token.is_eof() {
+            let text = token_text(&token.token.token);
+            insert_text(&mut string, &text, space(last, &token.token.token));
+            last = Last::from(&token.token.token);
+        }
+
+        for trivia in &token.trailing {
+            insert_trivia(&mut string, trivia, &mut last);
+        }
+    }
+
+    string
+}
+
+fn insert_trivia(string: &mut String, trivia: &Trivia, last: &mut Last) {
+    if let Trivia::Comment(comment) = trivia {
+        // a line comment keeps its newline, or it would swallow the rest
+        let comment = if comment.starts_with("//") {
+            comment
+        } else {
+            comment.trim_end()
+        };
+
+        insert_text(string, comment, *last != Last::Nothing);
+        *last = Last::Comment;
+    }
+}
+
+fn insert_text(string: &mut String, text: &str, space: bool) {
+    if space && !string.ends_with('\n') {
+        string.push(' ');
+    }
+
+    string.push_str(text);
+}
+
+fn token_text(token: &TokenVariant) -> String {
+    match token {
+        TokenVariant::Keyword(keyword) => keyword.as_str().into(),
+        TokenVariant::Ty(ty) => ty.as_str().into(),
+        TokenVariant::Ident(ident) => ident.inner().into(),
+        TokenVariant::Literal(Literal::Int(int)) => int.to_string(),
+        TokenVariant::Literal(Literal::String(string)) => format!(r#""{}""#, string),
+        TokenVariant::Literal(Literal::DynString(segs)) => dyn_string(segs),
+        TokenVariant::Symbol(symbol) => symbol.as_char().to_string(),
+        TokenVariant::Error(_) => String::new(),
+        TokenVariant::Eof => String::new(),
+    }
+}
@@ -0,0 +1,103 @@
+use crate::lexer::Ident;
+use crate::lexer::Position;
+
+use super::error::*;
+use super::value::Value;
+use super::Interpreter;
+
+pub(super) fn call<'c>(
+    interpreter: &Interpreter,
+    func: &Ident<'c>,
+    args: Vec<Value>,
+    pos: Position,
+) -> Result<'c, Value> {
+    match func.inner() {
+        "printf" => printf(interpreter, args),
+        "abort" => match args.as_slice() {
+            [Value::Str(message)] => Err(Error::abort(message.clone())),
+            _ => Err(Error::wrong_args(func.clone(), 1, args.len())),
+        },
+        "panic" => match args.as_slice() {
+            [Value::Str(message)] => Err(Error::panic(message.clone(), pos)),
+            _ => Err(Error::wrong_args(func.clone(), 1, args.len())),
+        },
+        "len" => match args.as_slice() {
+            [Value::Str(string)] => Ok(Value::I32(string.len() as i32)),
+            _ => Err(Error::wrong_args(func.clone(), 1, args.len())),
+        },
+        "concat" => match args.as_slice() {
+            [Value::Str(left), Value::Str(right)] => Ok(Value::Str(format!("{}{}", left, right))),
+            _ => Err(Error::wrong_args(func.clone(), 2, args.len())),
+        },
+        "compare" => match args.as_slice() {
+            [Value::Str(left), Value::Str(right)] => {
+                Ok(Value::I32(left.as_bytes().cmp(right.as_bytes()) as i32))
+            }
+            _ => Err(Error::wrong_args(func.clone(), 2, args.len())),
+        },
+        "slice" => match args.as_slice() {
+            [Value::Str(string), Value::I32(start), Value::I32(end)] => {
+                Ok(Value::Str(slice(string, *start, *end)))
+            }
+            _ => Err(Error::wrong_args(func.clone(), 3, args.len())),
+        },
+        "parse_int" => match args.as_slice() {
+            [Value::Str(string)] => Ok(Value::I32(parse_int(string))),
+            _ => Err(Error::wrong_args(func.clone(), 1, args.len())),
+        },
+        "to_str" => match args.as_slice() {
+            [Value::I32(int)] => Ok(Value::Str(int.to_string())),
+            _ => Err(Error::wrong_args(func.clone(), 1, args.len())),
+        },
+        "count" => match args.as_slice() {
+            [Value::StrList(list)] => Ok(Value::I32(list.len() as i32)),
+            _ => Err(Error::wrong_args(func.clone(), 1, args.len())),
+        },
+        "at" => match args.as_slice() {
+            [Value::StrList(list), Value::I32(index)] => match list.get(*index as usize) {
+                Some(string) if *index >= 0 => Ok(Value::Str(string.clone())),
+                _ => Err(Error::abort("index out of bounds".into())),
+            },
+            _ => Err(Error::wrong_args(func.clone(), 2, args.len())),
+        },
+        _ => Err(Error::unknown_func(func.clone())),
+    }
+}
+
+// same clamping as the compiled runtime, on bytes
+fn slice(string: &str, start: i32, end: i32) -> String {
+    let len = string.len() as i32;
+    let start = start.max(0).min(len);
+    let end = end.max(start).min(len);
+
+    String::from_utf8_lossy(&string.as_bytes()[start as usize..end as usize]).into_owned()
+}
+
+// behaves like `strtol` with base 10, which is what the compiled runtime calls
+fn parse_int(string: &str) -> i32 {
+    let string = string.trim_start();
+    let (sign, digits) = match string.chars().next() {
+        Some('-') => (-1, &string[1..]),
+        Some('+') => (1, &string[1..]),
+        _ => (1, string),
+    };
+
+    let mut int = 0i64;
+    for digit in digits.chars().take_while(char::is_ascii_digit) {
+        int = int
+            .saturating_mul(10)
+            .saturating_add(digit as i64 - '0' as i64);
+    }
+
+    (sign * int) as i32
+}
+
+// the string is printed as is, like the compiled runtime does
+fn printf<'p>(interpreter: &Interpreter, args: Vec<Value>) -> Result<'p, Value> {
+    match args.as_slice() {
+        [Value::Str(string)] => interpreter.print(string),
+        _ => return Err(Error::wrong_args(Ident::Ref("printf"), 1, args.len())),
+    }
+
+    Ok(Value::Void)
+}
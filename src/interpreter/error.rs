@@ -0,0 +1,93 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::lexer::Ident;
+use crate::lexer::Position;
+
+pub type Result<'r, OK> = std::result::Result<OK, Error<'r>>;
+
+#[derive(Debug)]
+pub struct Error<'e> {
+    kind: ErrorKind<'e>,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind<'e> {
+    UnknownFunc(Ident<'e>),
+    UnknownVar(Ident<'e>),
+    WrongArgs {
+        func: Ident<'e>,
+        expected: usize,
+        got: usize,
+    },
+    Abort(String),
+    Panic {
+        message: String,
+        pos: Position,
+    },
+}
+
+impl<'e> Error<'e> {
+    pub(super) fn unknown_func(func: Ident<'e>) -> Error<'e> {
+        Error {
+            kind: ErrorKind::UnknownFunc(func),
+        }
+    }
+
+    pub(super) fn unknown_var(var: Ident<'e>) -> Error<'e> {
+        Error {
+            kind: ErrorKind::UnknownVar(var),
+        }
+    }
+
+    pub(super) fn wrong_args(func: Ident<'e>, expected: usize, got: usize) -> Error<'e> {
+        Error {
+            kind: ErrorKind::WrongArgs {
+                func,
+                expected,
+                got,
+            },
+        }
+    }
+
+    pub(super) fn abort(message: String) -> Error<'e> {
+        Error {
+            kind: ErrorKind::Abort(message),
+        }
+    }
+
+    pub(super) fn panic(message: String, pos: Position) -> Error<'e> {
+        Error {
+            kind: ErrorKind::Panic { message, pos },
+        }
+    }
+}
+
+impl<'e> Display for Error<'e> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::UnknownFunc(func) => write!(fmt, "unknown func {}", func.inner()),
+            ErrorKind::UnknownVar(var) => write!(fmt, "unknown var {}", var.inner()),
+            ErrorKind::WrongArgs {
+                func,
+                expected,
+                got,
+            } => write!(
+                fmt,
+                "wrong number of args(func={}, expected={}, got={})",
+                func.inner(),
+                expected,
+                got
+            ),
+            ErrorKind::Abort(message) => write!(fmt, "fatal: {}", message),
+            ErrorKind::Panic { message, pos } => write!(
+                fmt,
+                "panicked at {}:{}: {}",
+                pos.line + 1,
+                pos.col + 1,
+                message
+            ),
+        }
+    }
+}
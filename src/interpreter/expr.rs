@@ -0,0 +1,136 @@
+use crate::lexer::FormatSpec;
+use crate::lexer::Ty;
+use crate::parser::expr::DynStringSeg;
+use crate::parser::expr::Expr;
+use crate::parser::expr::Literal;
+
+use super::builtins;
+use super::error::*;
+use super::value::Value;
+use super::Interpreter;
+use super::Vars;
+
+impl<'e> Expr<'e> {
+    pub(super) fn eval(
+        &'e self,
+        interpreter: &Interpreter<'e>,
+        vars: &Vars<'e>,
+    ) -> Result<'e, Value> {
+        match self {
+            Expr::Literal(lit) => lit.eval(interpreter, vars),
+            Expr::Func(func) => {
+                let mut args = vec![];
+                for arg in func.args.inner() {
+                    args.push(arg.eval(interpreter, vars)?);
+                }
+
+                match interpreter.tree.funcs.get(&func.name) {
+                    Some(decl) if decl.pos.is_none() => {
+                        builtins::call(interpreter, &func.name, args, func.pos)
+                    }
+                    Some(decl) => decl.call(interpreter, args),
+                    None => Err(Error::unknown_func(func.name.clone())),
+                }
+            }
+            Expr::Var(var) => match vars.get(var) {
+                Some(value) => Ok(value.clone()),
+                None => Err(Error::unknown_var(var.clone())),
+            },
+        }
+    }
+}
+
+impl<'l> Literal<'l> {
+    fn eval(&'l self, interpreter: &Interpreter<'l>, vars: &Vars<'l>) -> Result<'l, Value> {
+        match self {
+            Literal::Int { id, int, .. } => match interpreter.tree.lits.get(id) {
+                Some(Ty::I64) => Ok(Value::I64(*int)),
+                _ => Ok(Value::I32(*int as i32)),
+            },
+            Literal::String { string, .. } => Ok(Value::Str(string.to_string())),
+            Literal::RefDynString { segs, .. } => {
+                let mut string = String::new();
+                for seg in *segs {
+                    seg.eval(interpreter, vars, &mut string)?;
+                }
+
+                Ok(Value::Str(string))
+            }
+            Literal::OwnedDynString { segs, .. } => {
+                let mut string = String::new();
+                for seg in segs {
+                    seg.eval(interpreter, vars, &mut string)?;
+                }
+
+                Ok(Value::Str(string))
+            }
+        }
+    }
+}
+
+impl<'s> DynStringSeg<'s> {
+    fn eval(
+        &'s self,
+        interpreter: &Interpreter<'s>,
+        vars: &Vars<'s>,
+        string: &mut String,
+    ) -> Result<'s, ()> {
+        match self {
+            DynStringSeg::String(string_) => string.push_str(string_),
+            DynStringSeg::Expr(expr, None) => {
+                string.push_str(&expr.eval(interpreter, vars)?.to_string())
+            }
+            DynStringSeg::Expr(expr, Some(spec)) => {
+                string.push_str(&format(&expr.eval(interpreter, vars)?, spec))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn format(value: &Value, spec: &FormatSpec) -> String {
+    let (sign, mut body) = match value {
+        Value::I32(int) => format_int(i64::from(*int), *int as u32 as u64, spec),
+        Value::I64(int) => format_int(*int, *int as u64, spec),
+        Value::Str(string) => match spec.precision {
+            Some(precision) => ("", string.chars().take(precision).collect()),
+            None => ("", string.clone()),
+        },
+        Value::StrList(_) | Value::Void => ("", String::new()), // the ast rejects these
+    };
+
+    if let (Value::I32(_), Some(precision)) | (Value::I64(_), Some(precision)) =
+        (value, spec.precision)
+    {
+        while body.len() < precision {
+            body.insert(0, '0');
+        }
+    }
+
+    let width = spec.width.unwrap_or(0);
+    let len = sign.len() + body.chars().count();
+    if len >= width {
+        return format!("{}{}", sign, body);
+    }
+
+    let pad = width - len;
+    if spec.left {
+        format!("{}{}{}", sign, body, " ".repeat(pad))
+    } else if spec.zero && spec.precision.is_none() {
+        format!("{}{}{}", sign, "0".repeat(pad), body)
+    } else {
+        format!("{}{}{}", " ".repeat(pad), sign, body)
+    }
+}
+
+// hex and octal print the bits, like `printf` does for the compiled code
+fn format_int(int: i64, bits: u64, spec: &FormatSpec) -> (&'static str, String) {
+    match spec.conv {
+        Some('x') => ("", format!("{:x}", bits)),
+        Some('X') => ("", format!("{:X}", bits)),
+        Some('o') => ("", format!("{:o}", bits)),
+        _ if int < 0 => ("-", (i128::from(int)).abs().to_string()),
+        _ => ("", int.to_string()),
+    }
+}
@@ -0,0 +1,47 @@
+use crate::ast::Func;
+use crate::ast::Stmt;
+
+use super::error::*;
+use super::value::Value;
+use super::Interpreter;
+use super::Vars;
+
+impl<'f> Func<'f> {
+    pub(super) fn call(
+        &'f self,
+        interpreter: &Interpreter<'f>,
+        args: Vec<Value>,
+    ) -> Result<'f, Value> {
+        if args.len() != self.args.len() {
+            return Err(Error::wrong_args(
+                self.name.clone(),
+                self.args.len(),
+                args.len(),
+            ));
+        }
+
+        let mut vars = Vars::default();
+        for (arg, value) in self.args.iter().zip(args) {
+            vars.insert(arg.name.as_ref(), value);
+        }
+
+        let mut next = self.start;
+        while let Some(next_) = next {
+            let stmt = interpreter.tree.stmts[next_].as_ref().unwrap(); // FIXME
+            match stmt {
+                Stmt::Let { let_, .. } => {
+                    let value = let_.value.eval(interpreter, &vars)?;
+                    vars.insert(let_.name.as_ref(), value);
+                }
+                Stmt::Return { ret, .. } => return ret.0.eval(interpreter, &vars),
+                Stmt::Expr { expr, .. } => {
+                    expr.eval(interpreter, &vars)?;
+                }
+            }
+
+            next = stmt.next();
+        }
+
+        Ok(Value::Void)
+    }
+}
@@ -0,0 +1,192 @@
+use std::cell::RefCell;
+use std::io;
+use std::io::Write;
+
+use fnv::FnvHashMap;
+
+use crate::ast::Tree;
+use crate::lexer::Ident;
+use crate::parser::expr::Expr;
+
+mod builtins;
+mod error;
+mod expr;
+mod func;
+mod value;
+
+pub use error::*;
+pub use value::Value;
+
+pub type Vars<'v> = FnvHashMap<Ident<'v>, Value>;
+
+#[derive(Debug)]
+pub struct Interpreter<'i> {
+    tree: &'i Tree<'i>,
+    // what `printf` writes goes here instead of stdout when set
+    output: Option<RefCell<String>>,
+}
+
+impl<'i> Interpreter<'i> {
+    pub fn new(tree: &'i Tree<'i>) -> Interpreter<'i> {
+        Interpreter { tree, output: None }
+    }
+
+    pub fn run(&self, args: &[String]) -> Result<'i, i32> {
+        let name = Ident::Ref("main");
+        let main = match self.tree.funcs.get(&name) {
+            Some(main) => main,
+            None => return Err(Error::unknown_func(name)),
+        };
+
+        let args = if main.args.is_empty() {
+            vec![]
+        } else {
+            vec![Value::StrList(args.to_vec())]
+        };
+
+        match main.call(self, args)? {
+            Value::I32(code) => Ok(code),
+            _ => Ok(0),
+        }
+    }
+
+    pub fn eval(&self, expr: &'i Expr<'i>, vars: &Vars<'i>) -> Result<'i, Value> {
+        expr.eval(self, vars)
+    }
+
+    fn print(&self, string: &str) {
+        match &self.output {
+            Some(output) => output.borrow_mut().push_str(string),
+            None => {
+                print!("{}", string);
+                io::stdout().flush().ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+    use crate::lexer::lex;
+    use crate::parser::parse;
+
+    // the exit code or the error, and what was printed on the way
+    fn run(input: &str, args: &[&str]) -> (std::result::Result<i32, String>, String) {
+        let tokens = lex(input).unwrap();
+        let module = parse(&tokens).unwrap();
+        let mut tree = Tree::build(&[&module]);
+        tree.declare_builtins();
+        tree.infer();
+        tree.verify().unwrap();
+
+        let interpreter = Interpreter {
+            tree: &tree,
+            output: Some(RefCell::default()),
+        };
+        let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let code = interpreter.run(&args).map_err(|err| err.to_string());
+
+        (code, interpreter.output.unwrap().into_inner())
+    }
+
+    fn main(body: &str) -> String {
+        format!("func main(args: [str]): i32 {{ {} }}", body)
+    }
+
+    #[test]
+    fn runs_the_examples() {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let expected = [
+            ("arguments.kt", "Hello, World!"),
+            ("calls_and_lets.kt", "beforebetweenafter"),
+            (
+                "dyn_strings.kt",
+                "Hello, kitty!\nanswer == 42 (0x002a)\n   [Hello]\n[42   ]\n",
+            ),
+            ("empty_main.kt", ""),
+            ("inference.kt", "5 7 5000000000 12a05f200\n3 3\n"),
+            ("int_and_string.kt", "int == 12; string == Hello, \"World\""),
+            ("lowering.kt", "3 2 1 2 hi kitty\nfirst second third 00\n"),
+            ("main_args.kt", "3 args, running main_args.kt\n"),
+            ("return.kt", "Hello, World!"),
+            (
+                "strings.kt",
+                "kitty has 5 bytes\nitt ty\n-1 0 1\n-42 #-42\n",
+            ),
+        ];
+
+        for (example, output) in &expected {
+            let input = fs::read_to_string(examples.join(example)).unwrap();
+            let ran = run(&input, &[example, "a", "b"]);
+            assert_eq!(ran, (Ok(0), output.to_string()), "{}", example);
+        }
+    }
+
+    #[test]
+    fn stops_the_examples_at_a_panic() {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let input = fs::read_to_string(examples.join("panic.kt")).unwrap();
+
+        assert_eq!(
+            run(&input, &["panic.kt"]),
+            (
+                Err("panicked at 3:2: wrong answer: 41".into()),
+                "checking 41\n".into()
+            )
+        );
+    }
+
+    #[test]
+    fn prints_strs_verbatim() {
+        let (_, output) = run(&main(r#"printf("100%d %s\n"); return 0;"#), &[]);
+        assert_eq!(output, "100%d %s\n");
+    }
+
+    #[test]
+    fn slices_with_clamped_bounds() {
+        let body = r#"let minus = parse_int("-2");
+            printf(`${slice("kitty", 1, 3)}|${slice("kitty", minus, 99)}|${slice("kitty", 4, 2)}|`);
+            return 0;"#;
+        assert_eq!(run(&main(body), &[]).1, "it|kitty||");
+    }
+
+    #[test]
+    fn parses_ints_like_strtol() {
+        let parse = |string: &str| run(&main(&format!("return parse_int({:?});", string)), &[]).0;
+
+        assert_eq!(parse(" \t-12x"), Ok(-12));
+        assert_eq!(parse("+7"), Ok(7));
+        assert_eq!(parse("kitty"), Ok(0));
+        assert_eq!(parse("99999999999999999999"), Ok(-1));
+    }
+
+    #[test]
+    fn counts_and_indexes_args() {
+        let body = r#"printf(at(args, 1)); return count(args);"#;
+        assert_eq!(run(&main(body), &["main", "a", "b"]), (Ok(3), "a".into()));
+
+        assert_eq!(
+            run(&main("printf(at(args, 3)); return 0;"), &["main", "a", "b"]).0,
+            Err("fatal: index out of bounds".into())
+        );
+        assert_eq!(
+            run(
+                &main(r#"printf(at(args, parse_int("-1"))); return 0;"#),
+                &["main"]
+            )
+            .0,
+            Err("fatal: index out of bounds".into())
+        );
+    }
+
+    #[test]
+    fn exits_with_what_main_returns() {
+        assert_eq!(run(&main("return 3;"), &[]).0, Ok(3));
+        assert_eq!(run("func main() {}", &[]).0, Ok(0));
+        assert_eq!(run(&main(r#"return parse_int("-1");"#), &[]).0, Ok(-1));
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::lexer::Ty;
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum Value {
+    I32(i32),
+    I64(i64),
+    Str(String),
+    StrList(Vec<String>),
+    Void,
+}
+
+impl Value {
+    pub fn ty(&self) -> Ty {
+        match self {
+            Value::I32(_) => Ty::I32,
+            Value::I64(_) => Ty::I64,
+            Value::Str(_) => Ty::Str,
+            Value::StrList(_) => Ty::StrList,
+            Value::Void => Ty::Void,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Value::I32(int) => write!(fmt, "{}", int),
+            Value::I64(int) => write!(fmt, "{}", int),
+            Value::Str(string) => write!(fmt, "{}", string),
+            Value::StrList(list) => write!(fmt, "{:?}", list),
+            Value::Void => Ok(()),
+        }
+    }
+}
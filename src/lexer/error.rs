@@ -14,6 +14,15 @@ pub struct Error {
 #[derive(Eq, PartialEq, Debug)]
 pub enum ErrorKind {
     NotHandled(Position),
+    InvalidChar(char, Position),
+    InvalidEscape(char, Position),
+    BadNumber(Position),
+    UnterminatedString(Position),
+    UnterminatedComment(Position),
+    UnterminatedInsert(Position),
+    EmptyInsert(Position),
+    InvalidFormatSpec(Position),
+    Multiple(Vec<Error>),
 }
 
 impl Error {
@@ -22,12 +31,112 @@ impl Error {
             kind: ErrorKind::NotHandled(pos),
         }
     }
+
+    pub(super) fn invalid_char(chr: char, pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::InvalidChar(chr, pos),
+        }
+    }
+
+    pub(super) fn invalid_escape(chr: char, pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::InvalidEscape(chr, pos),
+        }
+    }
+
+    pub(super) fn bad_number(pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::BadNumber(pos),
+        }
+    }
+
+    pub(super) fn unterminated_string(pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::UnterminatedString(pos),
+        }
+    }
+
+    pub(super) fn unterminated_comment(pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::UnterminatedComment(pos),
+        }
+    }
+
+    pub(super) fn unterminated_insert(pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::UnterminatedInsert(pos),
+        }
+    }
+
+    pub(super) fn empty_insert(pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::EmptyInsert(pos),
+        }
+    }
+
+    pub(super) fn invalid_format_spec(pos: Position) -> Error {
+        Error {
+            kind: ErrorKind::InvalidFormatSpec(pos),
+        }
+    }
+
+    pub(super) fn multiple(errors: Vec<Error>) -> Error {
+        Error {
+            kind: ErrorKind::Multiple(errors),
+        }
+    }
+
+    pub fn pos(&self) -> Option<Position> {
+        match self.kind {
+            ErrorKind::NotHandled(pos) => Some(pos),
+            ErrorKind::InvalidChar(_, pos) => Some(pos),
+            ErrorKind::InvalidEscape(_, pos) => Some(pos),
+            ErrorKind::BadNumber(pos) => Some(pos),
+            ErrorKind::UnterminatedString(pos) => Some(pos),
+            ErrorKind::UnterminatedComment(pos) => Some(pos),
+            ErrorKind::UnterminatedInsert(pos) => Some(pos),
+            ErrorKind::EmptyInsert(pos) => Some(pos),
+            ErrorKind::InvalidFormatSpec(pos) => Some(pos),
+            ErrorKind::Multiple(_) => None,
+        }
+    }
+
+    pub fn errors(&self) -> Vec<&Error> {
+        if let ErrorKind::Multiple(errors) = &self.kind {
+            errors.iter().flat_map(|error| error.errors()).collect()
+        } else {
+            vec![self]
+        }
+    }
+
+    pub(super) fn is_not_handled(&self) -> bool {
+        matches!(self.kind, ErrorKind::NotHandled(_))
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        match self.kind {
-            ErrorKind::NotHandled(pos) => write!(fmt, "input not handled by token at {}", pos),
+        match &self.kind {
+            ErrorKind::NotHandled(_) => write!(fmt, "input not handled by token"),
+            ErrorKind::InvalidChar(chr, _) => write!(fmt, "invalid character {:?}", chr),
+            ErrorKind::InvalidEscape(chr, _) => {
+                write!(fmt, r#"invalid escape sequence "\{}""#, chr)
+            }
+            ErrorKind::BadNumber(_) => write!(fmt, "invalid number literal"),
+            ErrorKind::UnterminatedString(_) => write!(fmt, "unterminated string literal"),
+            ErrorKind::UnterminatedComment(_) => write!(fmt, "unterminated block comment"),
+            ErrorKind::UnterminatedInsert(_) => write!(fmt, "unterminated `${{` insert"),
+            ErrorKind::EmptyInsert(_) => write!(fmt, "empty interpolation"),
+            ErrorKind::InvalidFormatSpec(_) => write!(fmt, "invalid format spec"),
+            ErrorKind::Multiple(errors) => {
+                write!(fmt, "multiple errors: [")?;
+
+                for error in errors {
+                    write!(fmt, " {} ", error)?;
+                }
+
+                write!(fmt, "]")
+            }
         }
     }
 }
@@ -1,38 +1,57 @@
 mod error;
+mod stream;
 mod token;
+mod trivia;
 
 pub use error::*;
+pub use stream::Lexer;
 pub use token::*;
+pub use trivia::lex_trivia;
+pub use trivia::Trivia;
+pub use trivia::TriviaToken;
 
-pub fn lex<'i>(mut input: &'i str) -> Result<Vec<Token<'i>>> {
+pub fn lex<'i>(input: &'i str) -> Result<Vec<Token<'i>>> {
     let mut tokens = vec![];
+    let mut errors = vec![];
 
-    let mut pos = Position::default();
-    loop {
-        if input.starts_with(' ') {
-            pos.col += 1;
-            input = split(input, 1);
-        } else if input.starts_with('\t') {
-            pos.col += 4;
-            input = split(input, 1);
-        } else if input.starts_with('\n') {
-            pos.line += 1;
-            pos.col = 0;
-            input = split(input, 1);
-        } else {
-            let (input_, token) = Token::lex(input, &mut pos)?;
-            input = input_;
-
-            if let Some(token) = token {
-                tokens.push(token);
-                if input.is_empty() {
-                    break;
-                }
-            }
+    for token in Lexer::new(input) {
+        match token {
+            Ok(token) => tokens.push(token),
+            Err(err) => errors.push(err),
         }
     }
 
-    Ok(tokens)
+    if errors.is_empty() {
+        Ok(tokens)
+    } else if errors.len() == 1 {
+        Err(errors.pop().unwrap())
+    } else {
+        Err(Error::multiple(errors))
+    }
+}
+
+pub fn lex_recover<'i>(input: &'i str) -> Vec<Token<'i>> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = vec![];
+
+    while let Some(token) = lexer.next_token() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+fn skip_whitespace<'i>(input: &'i str, pos: &mut Position) -> &'i str {
+    let rest = input.trim_start_matches([' ', '\t', '\n']);
+    advance(&input[..input.len() - rest.len()], pos);
+
+    rest
+}
+
+fn advance(input: &str, pos: &mut Position) {
+    for chr in input.chars() {
+        pos.advance(chr);
+    }
 }
 
 fn split(data: &str, at: usize) -> &str {
@@ -42,3 +61,73 @@ fn split(data: &str, at: usize) -> &str {
         &data[at..]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(col: usize) -> Position {
+        Position {
+            offset: col,
+            line: 0,
+            col,
+        }
+    }
+
+    #[test]
+    fn recovers_until_eof_after_trailing_invalid_char() {
+        let input = "let a = 1; # let b = 2; @";
+
+        let errors = Lexer::new(input)
+            .filter_map(|token| token.err())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                Error::invalid_char('#', pos(11)),
+                Error::invalid_char('@', pos(24)),
+            ]
+        );
+
+        let tokens = lex_recover(input);
+        assert_eq!(tokens.len(), 13);
+        assert!(tokens[12].is_eof());
+        assert_eq!(tokens[12].pos, pos(25));
+    }
+
+    #[test]
+    fn recovers_until_eof_when_input_is_only_invalid_chars() {
+        let tokens = lex_recover("@#");
+        assert_eq!(tokens.len(), 3);
+        assert!(tokens[2].is_eof());
+
+        match lex("@#") {
+            Err(err) => assert_eq!(
+                err.errors(),
+                vec![
+                    &Error::invalid_char('@', pos(0)),
+                    &Error::invalid_char('#', pos(1)),
+                ]
+            ),
+            Ok(_) => panic!("expected errors"),
+        }
+    }
+
+    #[test]
+    fn recovers_until_eof_after_unterminated_literal() {
+        let tokens = lex_recover("@ `abc");
+        assert_eq!(tokens.len(), 3);
+        assert!(tokens[2].is_eof());
+
+        match lex("@ /* never closed") {
+            Err(err) => assert_eq!(
+                err.errors(),
+                vec![
+                    &Error::invalid_char('@', pos(0)),
+                    &Error::unterminated_comment(pos(2)),
+                ]
+            ),
+            Ok(_) => panic!("expected errors"),
+        }
+    }
+}
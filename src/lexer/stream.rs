@@ -0,0 +1,70 @@
+use super::error::*;
+use super::skip_whitespace;
+use super::Position;
+use super::Token;
+use super::TokenVariant;
+
+#[derive(Debug)]
+pub struct Lexer<'l> {
+    input: &'l str,
+    pos: Position,
+    peeked: Option<Option<Result<Token<'l>>>>,
+    eof: bool,
+}
+
+impl<'l> Lexer<'l> {
+    pub fn new(input: &'l str) -> Lexer<'l> {
+        Lexer {
+            input,
+            pos: Position::default(),
+            peeked: None,
+            eof: false,
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&Result<Token<'l>>> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.lex());
+        }
+
+        self.peeked.as_ref().unwrap().as_ref()
+    }
+
+    pub(super) fn next_token(&mut self) -> Option<Token<'l>> {
+        loop {
+            if self.eof {
+                return None;
+            }
+
+            self.input = skip_whitespace(self.input, &mut self.pos);
+
+            let (input, token) = Token::lex(self.input, &mut self.pos);
+            self.input = input;
+
+            if let Some(token) = token {
+                self.eof = token.is_eof();
+                return Some(token);
+            }
+        }
+    }
+
+    fn lex(&mut self) -> Option<Result<Token<'l>>> {
+        let token = self.next_token()?;
+        if let TokenVariant::Error(err) = token.token {
+            Some(Err(err))
+        } else {
+            Some(Ok(token))
+        }
+    }
+}
+
+impl<'l> Iterator for Lexer<'l> {
+    type Item = Result<Token<'l>>;
+
+    fn next(&mut self) -> Option<Result<Token<'l>>> {
+        match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.lex(),
+        }
+    }
+}
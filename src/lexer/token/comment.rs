@@ -1,3 +1,4 @@
+use super::advance;
 use super::error::*;
 use super::split;
 use super::Position;
@@ -6,56 +7,42 @@ pub struct Comment;
 
 impl Comment {
     pub(super) fn lex<'i>(input: &'i str, pos: &mut Position) -> Result<&'i str> {
-        let mut tpos = *pos;
-        let mut line = vec![];
-        let mut block = vec![];
-
         if input.starts_with("//") {
-            tpos.col += 2;
-            line.push(2);
-        } else if input.starts_with("/*") {
-            tpos.col += 2;
-            block.push(2);
-        } else {
+            let end = match input.find('\n') {
+                Some(end) => end + 1,
+                None => input.len(),
+            };
+
+            advance(&input[..end], pos);
+            return Ok(split(input, end));
+        } else if !input.starts_with("/*") {
             return Err(Error::not_handled(*pos));
         }
 
+        let mut depth = 1;
         let mut i = 2;
         loop {
-            if line.is_empty() && block.is_empty() {
-                break;
-            }
-
-            if input.is_empty() {
-                return Err(Error::not_handled(*pos));
+            let rest = split(input, i);
+            if rest.is_empty() {
+                return Err(Error::unterminated_comment(*pos));
             }
 
-            if split(input, i).starts_with("/*") {
+            if rest.starts_with("/*") {
                 i += 2;
-                tpos.col += 2;
-                block.push(i);
-                continue;
-            }
-
-            if split(input, i).starts_with("*/") {
+                depth += 1;
+            } else if rest.starts_with("*/") {
                 i += 2;
-                tpos.col += 2;
-                block.pop();
-                continue;
-            }
+                depth -= 1;
 
-            if split(input, i).starts_with('\n') {
-                i += 1;
-                tpos.line += 1;
-                tpos.col = 0;
-                line.pop();
-                continue;
+                if depth == 0 {
+                    break;
+                }
+            } else {
+                i += rest.chars().next().unwrap().len_utf8();
             }
-
-            i += 1;
         }
 
-        *pos = tpos;
+        advance(&input[..i], pos);
         Ok(split(input, i))
     }
 }
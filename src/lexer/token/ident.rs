@@ -4,11 +4,14 @@ use std::fmt::Formatter;
 use std::hash::Hash;
 use std::hash::Hasher;
 
+use super::advance;
 use super::error::*;
 use super::split;
+use super::Keyword;
 use super::Position;
 use super::Token;
 use super::TokenVariant;
+use super::Ty;
 
 #[derive(Clone, Debug)]
 pub enum Ident<'i> {
@@ -35,7 +38,7 @@ impl<'i> Ident<'i> {
         let mut i = 0;
         for chr in input.chars() {
             if chr.is_alphabetic() || chr == '_' || (chr.is_alphanumeric() && i > 0) {
-                i += 1;
+                i += chr.len_utf8();
             } else {
                 break;
             }
@@ -43,12 +46,18 @@ impl<'i> Ident<'i> {
 
         if i > 0 {
             let tpos = *pos;
-            pos.col += i;
+            advance(&input[..i], pos);
 
-            Ok((
-                split(input, i),
-                Ident::Ref(input.get(0..i).unwrap()).token(tpos),
-            ))
+            let ident = input.get(0..i).unwrap();
+            let token = if let Some(keyword) = Keyword::from_ident(ident) {
+                keyword.token(tpos)
+            } else if let Some(ty) = Ty::from_ident(ident) {
+                ty.token(tpos)
+            } else {
+                Ident::Ref(ident).token(tpos)
+            };
+
+            Ok((split(input, i), token))
         } else {
             Err(Error::not_handled(*pos))
         }
@@ -84,3 +93,85 @@ impl<'i> Display for Ident<'i> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::lex_recover;
+
+    use super::super::keyword::KEYWORDS;
+    use super::super::ty::TYS;
+
+    fn tokens(input: &str) -> Vec<String> {
+        lex_recover(input)
+            .iter()
+            .map(|token| token.token.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn keywords_at_end_of_input() {
+        for (name, keyword) in KEYWORDS {
+            assert_eq!(tokens(name), vec![keyword.to_string(), "eof".into()]);
+            assert_eq!(
+                tokens(&format!("x {}", name)),
+                vec!["ident(x)".into(), keyword.to_string(), "eof".into()]
+            );
+        }
+    }
+
+    #[test]
+    fn tys_at_end_of_input() {
+        for (name, ty) in TYS {
+            assert_eq!(tokens(name), vec![ty.to_string(), "eof".into()]);
+            assert_eq!(
+                tokens(&format!("x: {}", name)),
+                vec![
+                    "ident(x)".into(),
+                    "symbol(\":\")".into(),
+                    ty.to_string(),
+                    "eof".into(),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn keywords_and_tys_in_longer_idents() {
+        let names = KEYWORDS.iter().map(|(name, _)| name).chain(
+            TYS.iter()
+                .filter(|(name, _)| !name.starts_with('['))
+                .map(|(name, _)| name),
+        );
+
+        for name in names {
+            for ident in &[
+                format!("x{}", name),
+                format!("{}x", name),
+                format!("_{}", name),
+                format!("{}_", name),
+                format!("{}1", name),
+                format!("{}{}", name, name),
+            ] {
+                assert_eq!(
+                    tokens(ident),
+                    vec![format!("ident({})", ident), "eof".into()]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn keywords_next_to_symbols() {
+        assert_eq!(
+            tokens("return(letter);"),
+            vec![
+                "keyword::return",
+                "symbol(\"(\")",
+                "ident(letter)",
+                "symbol(\")\")",
+                "symbol(\";\")",
+                "eof",
+            ]
+        );
+    }
+}
@@ -2,8 +2,6 @@ use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
-use super::error::*;
-use super::split;
 use super::Position;
 use super::Token;
 use super::TokenVariant;
@@ -15,27 +13,29 @@ pub enum Keyword {
     Return,
 }
 
-impl Keyword {
-    pub(super) fn lex<'i>(input: &'i str, pos: &mut Position) -> Result<(&'i str, Token<'i>)> {
-        let tpos = *pos;
-        if input.starts_with("func") {
-            pos.col += 4;
-
-            Ok((split(input, 4), Keyword::Func.token(tpos)))
-        } else if input.starts_with("let") {
-            pos.col += 3;
+pub(super) const KEYWORDS: &[(&str, Keyword)] = &[
+    ("func", Keyword::Func),
+    ("let", Keyword::Let),
+    ("return", Keyword::Return),
+];
 
-            Ok((split(input, 3), Keyword::Let.token(tpos)))
-        } else if input.starts_with("return") {
-            pos.col += 6;
+impl Keyword {
+    pub(super) fn from_ident(ident: &str) -> Option<Keyword> {
+        KEYWORDS
+            .iter()
+            .find(|(name, _)| *name == ident)
+            .map(|(_, keyword)| *keyword)
+    }
 
-            Ok((split(input, 6), Keyword::Return.token(tpos)))
-        } else {
-            Err(Error::not_handled(tpos))
-        }
+    pub fn as_str(self) -> &'static str {
+        KEYWORDS
+            .iter()
+            .find(|(_, keyword)| *keyword == self)
+            .map(|(name, _)| *name)
+            .unwrap()
     }
 
-    fn token<'t>(self, pos: Position) -> Token<'t> {
+    pub(super) fn token<'t>(self, pos: Position) -> Token<'t> {
         Token {
             token: TokenVariant::Keyword(self),
             pos,
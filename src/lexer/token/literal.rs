@@ -2,15 +2,18 @@ use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
+use super::advance;
 use super::error::*;
+use super::skip_whitespace;
 use super::split;
+use super::FormatSpec;
 use super::Position;
 use super::Token;
 use super::TokenVariant;
 
 #[derive(Debug)]
 pub enum Literal<'l> {
-    Int(i32),
+    Int(i64),
     String(&'l str),
     DynString(Vec<DynStringSeg<'l>>),
 }
@@ -18,162 +21,178 @@ pub enum Literal<'l> {
 #[derive(Debug)]
 pub enum DynStringSeg<'s> {
     String(&'s str),
-    Insert(Vec<Token<'s>>),
+    Insert(Vec<Token<'s>>, Option<FormatSpec>, &'s str),
 }
 
+const STRING_ESCAPES: &str = "\\\"ntr0";
+const DYN_STRING_ESCAPES: &str = "\\`$ntr0";
+
 impl<'l> Literal<'l> {
     pub(super) fn lex(input: &'l str, pos: &mut Position) -> Result<(&'l str, Token<'l>)> {
         match (input.get(0..1), input.get(1..)) {
             (Some(_), None) => Err(Error::not_handled(*pos)),
             (Some(r#"""#), Some(rest)) => {
                 let tpos = *pos;
+                pos.advance('"');
 
+                let mut error = None;
                 let mut esc = false;
-                let mut i = 0;
-                for chr in rest.chars() {
-                    match chr {
-                        '\\' => {
-                            esc = true;
-                            i += 1;
-                            pos.col += 1;
-                            continue;
-                        }
-                        '"' => {
-                            i += 1;
-                            pos.col += 1;
-
-                            if !esc {
-                                return Ok((
-                                    split(rest, i),
-                                    Literal::String(&rest[0..i - 1]).token(tpos),
-                                ));
-                            }
-                        }
-                        '\n' => {
-                            i += 1;
-                            pos.line += 1;
-                            pos.col = 0;
-                        }
-                        _ => {
-                            i += 1;
-                            pos.col += 1;
+                let mut epos = *pos;
+                for (i, chr) in rest.char_indices() {
+                    if esc {
+                        if !STRING_ESCAPES.contains(chr) && error.is_none() {
+                            error = Some(Error::invalid_escape(chr, epos));
                         }
+
+                        esc = false;
+                    } else if chr == '\\' {
+                        esc = true;
+                        epos = *pos;
+                    } else if chr == '"' {
+                        pos.advance(chr);
+
+                        let token = match error {
+                            Some(err) => Token::error(err, tpos),
+                            None => Literal::String(&rest[..i]).token(tpos),
+                        };
+
+                        return Ok((split(rest, i + 1), token));
                     }
 
-                    esc = false;
+                    pos.advance(chr);
                 }
 
-                let epos = *pos;
-                *pos = tpos;
-
-                Err(Error::not_handled(epos))
+                Ok(("", Token::error(Error::unterminated_string(tpos), tpos)))
             }
             (Some("`"), Some(rest)) => {
                 let tpos = *pos;
+                pos.advance('`');
 
+                let mut error = None;
                 let mut esc = false;
+                let mut epos = *pos;
                 let mut segs = vec![];
                 let mut last = 0;
                 let mut i = 0;
 
-                'main: loop {
-                    if rest.len() <= i {
-                        break;
-                    }
-
-                    match &rest[i..=i] {
-                        "\\" => {
-                            esc = true;
-                            pos.col += 1;
-                            continue;
+                while let Some(chr) = split(rest, i).chars().next() {
+                    if esc {
+                        if !DYN_STRING_ESCAPES.contains(chr) && error.is_none() {
+                            error = Some(Error::invalid_escape(chr, epos));
                         }
-                        "`" => {
-                            i += 1;
-                            pos.col += 1;
 
-                            if !esc {
-                                segs.push(DynStringSeg::String(&rest[last..i - 1]));
-
-                                return Ok((split(rest, i), Literal::DynString(segs).token(tpos)));
-                            }
-                        }
-                        "$" => {
-                            i += 1;
-                            pos.col += 1;
-
-                            if &rest[i..=i] == "{" {
-                                segs.push(DynStringSeg::String(&rest[last..i - 1]));
+                        esc = false;
+                    } else if chr == '\\' {
+                        esc = true;
+                        epos = *pos;
+                    } else if chr == '`' {
+                        pos.advance(chr);
+
+                        segs.push(DynStringSeg::String(&rest[last..i]));
+
+                        let token = match error {
+                            Some(err) => Token::error(err, tpos),
+                            None => Literal::DynString(segs).token(tpos),
+                        };
+
+                        return Ok((split(rest, i + 1), token));
+                    } else if chr == '$' && split(rest, i + 1).starts_with('{') {
+                        let ipos = *pos;
+
+                        segs.push(DynStringSeg::String(&rest[last..i]));
+                        advance(&rest[i..i + 2], pos);
+                        i += 2;
+
+                        let start = i;
+                        let mut end = None;
+                        let mut tokens = vec![];
+                        let mut spec = None;
+                        loop {
+                            let rest_ = skip_whitespace(split(rest, i), pos);
+                            i = rest.len() - rest_.len();
+
+                            if rest_.is_empty() {
+                                let err = Error::unterminated_insert(ipos);
+                                return Ok(("", Token::error(err, tpos)));
+                            } else if rest_.starts_with('}') {
+                                end = end.or(Some(i));
+                                pos.advance('}');
                                 i += 1;
-                                pos.col += 1;
 
-                                let mut tokens = vec![];
-                                loop {
-                                    if &rest[i..=i] == "}" {
-                                        i += 1;
-                                        pos.col += 1;
+                                break;
+                            } else if rest_.starts_with(':') && spec.is_none() {
+                                end = Some(i);
+                                let spos = *pos;
+                                let end = rest_.find('}').unwrap_or(rest_.len());
+                                advance(&rest_[..end], pos);
+                                i += end;
+
+                                spec = FormatSpec::parse(&rest_[1..end]);
+                                if spec.is_none() && error.is_none() {
+                                    error = Some(Error::invalid_format_spec(spos));
+                                }
 
-                                        break;
-                                    } else if rest.len() <= i {
-                                        break 'main;
-                                    }
+                                continue;
+                            }
+
+                            let (rest_, token) = Token::lex(rest_, pos);
+                            i = rest.len() - rest_.len();
 
-                                    match Token::lex(split(rest, i), pos)? {
-                                        (rest_, Some(token)) => {
-                                            tokens.push(token);
-                                            i = rest.len() - rest_.len();
-                                        }
-                                        (rest_, None) => i = rest.len() - rest_.len(),
+                            if let Some(token) = token {
+                                if let TokenVariant::Error(err) = token.token {
+                                    if error.is_none() {
+                                        error = Some(err);
                                     }
+                                } else {
+                                    tokens.push(token);
                                 }
-
-                                last = i;
-                                segs.push(DynStringSeg::Insert(tokens));
                             }
                         }
-                        "\n" => {
-                            i += 1;
-                            pos.line += 1;
-                            pos.col = 0;
-                        }
-                        _ => {
-                            i += 1;
-                            pos.col += 1;
+
+                        if tokens.is_empty() && error.is_none() {
+                            error = Some(Error::empty_insert(ipos));
                         }
+
+                        last = i;
+                        let source = &rest[start..end.unwrap_or(start)];
+                        segs.push(DynStringSeg::Insert(tokens, spec, source));
+                        continue;
                     }
 
-                    esc = false;
+                    pos.advance(chr);
+                    i += chr.len_utf8();
                 }
 
-                let epos = *pos;
-                *pos = tpos;
-
-                Err(Error::not_handled(epos))
+                Ok(("", Token::error(Error::unterminated_string(tpos), tpos)))
             }
             (Some(_), Some(_)) => {
                 let tpos = *pos;
 
+                let mut bad = false;
                 let mut i = 0;
                 for chr in input.chars() {
-                    if chr.is_numeric() {
+                    if chr.is_ascii_digit() && !bad {
                         i += 1;
-                        pos.col += 1;
-                    } else if i > 0 {
-                        return Ok((
-                            split(input, i),
-                            Literal::Int(
-                                i32::from_str_radix(&input[0..i], 10).unwrap(), // FIXME
-                            )
-                            .token(tpos),
-                        ));
+                    } else if i > 0 && (chr.is_alphanumeric() || chr == '_') {
+                        bad = true;
+                        i += chr.len_utf8();
                     } else {
                         break;
                     }
+
+                    pos.advance(chr);
+                }
+
+                if i == 0 {
+                    return Err(Error::not_handled(tpos));
                 }
 
-                let epos = *pos;
-                *pos = tpos;
+                let token = match input[0..i].parse::<i64>() {
+                    Ok(int) if !bad => Literal::Int(int).token(tpos),
+                    _ => Token::error(Error::bad_number(tpos), tpos),
+                };
 
-                Err(Error::not_handled(epos))
+                Ok((split(input, i), token))
             }
             _ => Err(Error::not_handled(*pos)),
         }
@@ -209,15 +228,88 @@ impl<'s> Display for DynStringSeg<'s> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match self {
             DynStringSeg::String(string) => write!(fmt, "seg::string({:?})", string),
-            DynStringSeg::Insert(insert) => {
+            DynStringSeg::Insert(insert, spec, _) => {
                 write!(fmt, "seg::insert([")?;
 
                 for token in insert {
                     write!(fmt, " {} ", token)?;
                 }
 
-                write!(fmt, "])")
+                match spec {
+                    Some(spec) => write!(fmt, "], {:?})", spec.to_string()),
+                    None => write!(fmt, "])"),
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::lex;
+
+    fn pos(col: usize) -> Position {
+        Position {
+            offset: col,
+            line: 0,
+            col,
+        }
+    }
+
+    fn insert_spec(input: &str) -> Option<FormatSpec> {
+        let tokens = lex(input).unwrap();
+        match &tokens[0].token {
+            TokenVariant::Literal(Literal::DynString(segs)) => match &segs[1] {
+                DynStringSeg::Insert(_, spec, _) => *spec,
+                seg => panic!("expected an insert, got {}", seg),
+            },
+            token => panic!("expected a dyn string, got {:?}", token),
+        }
+    }
+
+    fn assert_error(input: &str, expected: Error) {
+        match lex(input) {
+            Err(err) => assert_eq!(err.errors(), vec![&expected], "{:?}", input),
+            Ok(_) => panic!("expected an error for {:?}", input),
+        }
+    }
+
+    #[test]
+    fn lexes_insert_specs() {
+        let spec = insert_spec("`${x:-3}`").unwrap();
+        assert!(spec.left);
+        assert_eq!(spec.width, Some(3));
+
+        let spec = insert_spec("`${f():08.2x}`").unwrap();
+        assert!(spec.zero);
+        assert_eq!(
+            (spec.width, spec.precision, spec.conv),
+            (Some(8), Some(2), Some('x'))
+        );
+
+        assert_eq!(insert_spec("`${x}`"), None);
+        assert_eq!(insert_spec("`${ x :-3}`").unwrap().to_string(), "-3");
+    }
+
+    #[test]
+    fn rejects_empty_inserts() {
+        assert_error("`${}`", Error::empty_insert(pos(1)));
+        assert_error("`${  }`", Error::empty_insert(pos(1)));
+        assert_error("`ab${:08}`", Error::empty_insert(pos(3)));
+    }
+
+    #[test]
+    fn rejects_invalid_insert_specs() {
+        assert_error("`${x:}`", Error::invalid_format_spec(pos(4)));
+        assert_error("`${x:--3}`", Error::invalid_format_spec(pos(4)));
+        assert_error("`${x:8d}`", Error::invalid_format_spec(pos(4)));
+        assert_error("`${x:.}`", Error::invalid_format_spec(pos(4)));
+    }
+
+    #[test]
+    fn rejects_unterminated_inserts() {
+        assert_error("`${x", Error::unterminated_insert(pos(1)));
+        assert_error("`${x:08", Error::unterminated_insert(pos(1)));
+    }
+}
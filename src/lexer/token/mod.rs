@@ -3,14 +3,17 @@ use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
+use super::advance;
 use super::error;
 use super::error::*;
+use super::skip_whitespace;
 use super::split;
 
 mod comment;
 mod ident;
 mod keyword;
 mod literal;
+mod spec;
 mod symbol;
 mod ty;
 
@@ -18,6 +21,7 @@ pub use ident::Ident;
 pub use keyword::Keyword;
 pub use literal::DynStringSeg;
 pub use literal::Literal;
+pub use spec::FormatSpec;
 pub use symbol::Symbol;
 pub use ty::Ty;
 
@@ -36,11 +40,13 @@ pub enum TokenVariant<'t> {
     Ident(Ident<'t>),
     Literal(Literal<'t>),
     Symbol(Symbol),
-    EOF,
+    Error(Error),
+    Eof,
 }
 
 #[derive(Default, Eq, PartialEq, Copy, Clone, Debug)]
 pub struct Position {
+    pub offset: usize,
     pub line: usize,
     pub col: usize,
 }
@@ -52,56 +58,69 @@ pub enum TokenTy {
     Ident,
     Literal,
     Symbol(Symbol),
-    EOF,
+    Eof,
 }
 
 impl<'t> Token<'t> {
-    pub(super) fn lex(input: &'t str, pos: &mut Position) -> Result<(&'t str, Option<Token<'t>>)> {
+    pub(super) fn lex(input: &'t str, pos: &mut Position) -> (&'t str, Option<Token<'t>>) {
         if input.is_empty() {
-            return Ok((
+            return (
                 input,
                 Some(Token {
-                    token: TokenVariant::EOF,
+                    token: TokenVariant::Eof,
                     pos: *pos,
                 }),
-            ));
+            );
         }
 
-        if let Ok(input) = Comment::lex(input, pos) {
-            return Ok((input, None));
-        }
-
-        if let Ok((input, token)) = Keyword::lex(input, pos) {
-            return Ok((input, Some(token)));
-        }
+        match Comment::lex(input, pos) {
+            Ok(input) => return (input, None),
+            Err(ref err) if err.is_not_handled() => (),
+            Err(err) => {
+                let tpos = *pos;
+                advance(input, pos);
 
-        if let Ok((input, token)) = Ty::lex(input, pos) {
-            return Ok((input, Some(token)));
+                return ("", Some(Token::error(err, tpos)));
+            }
         }
 
         if let Ok((input, token)) = Ident::lex(input, pos) {
-            return Ok((input, Some(token)));
+            return (input, Some(token));
         }
 
         if let Ok((input, token)) = Literal::lex(input, pos) {
-            return Ok((input, Some(token)));
+            return (input, Some(token));
+        }
+
+        if let Ok((input, token)) = Ty::lex(input, pos) {
+            return (input, Some(token));
         }
 
         if let Ok((input, token)) = Symbol::lex(input, pos) {
-            return Ok((input, Some(token)));
+            return (input, Some(token));
         }
 
-        Err(Error::not_handled(*pos))
+        let tpos = *pos;
+        let chr = input.chars().next().unwrap();
+        pos.advance(chr);
+
+        (
+            split(input, chr.len_utf8()),
+            Some(Token::error(Error::invalid_char(chr, tpos), tpos)),
+        )
     }
 
-    pub fn is_eof(&self) -> bool {
-        if let TokenVariant::EOF = self.token {
-            true
-        } else {
-            false
+    fn error(err: Error, pos: Position) -> Token<'t> {
+        Token {
+            token: TokenVariant::Error(err),
+            pos,
         }
     }
 
+    pub fn is_eof(&self) -> bool {
+        matches!(self.token, TokenVariant::Eof)
+    }
+
     pub fn eq_keyword(&self, keyword: Keyword) -> bool {
         if let TokenVariant::Keyword(keyword_) = self.token {
             keyword_ == keyword
@@ -119,6 +138,34 @@ impl<'t> Token<'t> {
     }
 }
 
+impl Position {
+    pub fn display_col(&self, input: &str, tab_width: usize) -> usize {
+        let line = match input[..self.offset].rfind('\n') {
+            Some(start) => &input[start + 1..self.offset],
+            None => &input[..self.offset],
+        };
+
+        line.chars().fold(0, |col, chr| {
+            if chr == '\t' {
+                col + tab_width - col % tab_width
+            } else {
+                col + 1
+            }
+        })
+    }
+
+    pub(super) fn advance(&mut self, chr: char) {
+        self.offset += chr.len_utf8();
+
+        if chr == '\n' {
+            self.line += 1;
+            self.col = 0;
+        } else {
+            self.col += 1;
+        }
+    }
+}
+
 impl Ord for Position {
     fn cmp(&self, other: &Position) -> Ordering {
         match (self.line.cmp(&other.line), self.col.cmp(&other.col)) {
@@ -130,15 +177,7 @@ impl Ord for Position {
 
 impl PartialOrd for Position {
     fn partial_cmp(&self, other: &Position) -> Option<Ordering> {
-        match (
-            self.line.partial_cmp(&other.line),
-            self.col.partial_cmp(&other.col),
-        ) {
-            (Some(Ordering::Equal), cmp) => cmp,
-            (Some(cmp), _) => Some(cmp),
-            (_, Some(cmp)) => Some(cmp),
-            _ => None,
-        }
+        Some(self.cmp(other))
     }
 }
 
@@ -156,7 +195,8 @@ impl<'t> Display for TokenVariant<'t> {
             TokenVariant::Ident(ident) => write!(fmt, "{}", ident),
             TokenVariant::Literal(literal) => write!(fmt, "{}", literal),
             TokenVariant::Symbol(symbol) => write!(fmt, "{}", symbol),
-            TokenVariant::EOF => write!(fmt, "eof"),
+            TokenVariant::Error(err) => write!(fmt, "error({})", err),
+            TokenVariant::Eof => write!(fmt, "eof"),
         }
     }
 }
@@ -175,7 +215,7 @@ impl Display for TokenTy {
             TokenTy::Ident => write!(fmt, "ident"),
             TokenTy::Literal => write!(fmt, "lit"),
             TokenTy::Symbol(symbol) => write!(fmt, "{}", symbol),
-            TokenTy::EOF => write!(fmt, "eof"),
+            TokenTy::Eof => write!(fmt, "eof"),
         }
     }
 }
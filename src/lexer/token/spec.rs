@@ -0,0 +1,160 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+const CONVS: &str = "xXo";
+
+#[derive(Default, Eq, PartialEq, Copy, Clone, Debug)]
+pub struct FormatSpec {
+    pub left: bool,
+    pub zero: bool,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+    pub conv: Option<char>,
+}
+
+impl FormatSpec {
+    pub(super) fn parse(spec: &str) -> Option<FormatSpec> {
+        let mut rest = spec;
+        let mut spec = FormatSpec::default();
+
+        if rest.starts_with('-') {
+            spec.left = true;
+            rest = &rest[1..];
+        }
+
+        if rest.starts_with('0') {
+            spec.zero = true;
+            rest = &rest[1..];
+        }
+
+        let (width, rest_) = number(rest);
+        spec.width = width;
+        rest = rest_;
+
+        if rest.starts_with('.') {
+            let (precision, rest_) = number(&rest[1..]);
+            spec.precision = Some(precision?);
+            rest = rest_;
+        }
+
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => (),
+            (Some(conv), None) if CONVS.contains(conv) => spec.conv = Some(conv),
+            _ => return None,
+        }
+
+        if spec == FormatSpec::default() || (spec.left && spec.width.is_none()) {
+            None
+        } else {
+            Some(spec)
+        }
+    }
+
+    pub fn needs_int(&self) -> bool {
+        self.zero || self.conv.is_some()
+    }
+}
+
+impl Display for FormatSpec {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if self.left {
+            write!(fmt, "-")?;
+        }
+
+        if self.zero {
+            write!(fmt, "0")?;
+        }
+
+        if let Some(width) = self.width {
+            write!(fmt, "{}", width)?;
+        }
+
+        if let Some(precision) = self.precision {
+            write!(fmt, ".{}", precision)?;
+        }
+
+        if let Some(conv) = self.conv {
+            write!(fmt, "{}", conv)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn number(input: &str) -> (Option<usize>, &str) {
+    let end = input
+        .find(|chr: char| !chr.is_ascii_digit())
+        .unwrap_or(input.len());
+
+    (input[..end].parse().ok(), &input[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(left: bool, zero: bool, width: Option<usize>, precision: Option<usize>) -> FormatSpec {
+        FormatSpec {
+            left,
+            zero,
+            width,
+            precision,
+            conv: None,
+        }
+    }
+
+    #[test]
+    fn parses_flags_width_and_precision() {
+        assert_eq!(
+            FormatSpec::parse("8"),
+            Some(spec(false, false, Some(8), None))
+        );
+        assert_eq!(
+            FormatSpec::parse("08"),
+            Some(spec(false, true, Some(8), None))
+        );
+        assert_eq!(
+            FormatSpec::parse("-3"),
+            Some(spec(true, false, Some(3), None))
+        );
+        assert_eq!(
+            FormatSpec::parse("-08"),
+            Some(spec(true, true, Some(8), None))
+        );
+        assert_eq!(
+            FormatSpec::parse(".2"),
+            Some(spec(false, false, None, Some(2)))
+        );
+        assert_eq!(
+            FormatSpec::parse("-6.2"),
+            Some(spec(true, false, Some(6), Some(2)))
+        );
+    }
+
+    #[test]
+    fn parses_conversions() {
+        for conv in CONVS.chars() {
+            let parsed = FormatSpec::parse(&format!("08{}", conv)).unwrap();
+            assert_eq!(parsed.conv, Some(conv));
+            assert!(parsed.needs_int());
+        }
+
+        assert!(!FormatSpec::parse("-3").unwrap().needs_int());
+    }
+
+    #[test]
+    fn rejects_invalid_specs() {
+        for input in &["", "-", "8.", "x8", "8xx", "8d", "--3", "0-3", " 8", "8 "] {
+            assert_eq!(FormatSpec::parse(input), None, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn displays_what_it_parses() {
+        for input in &["8", "08", "-3", "-08", ".2", "-6.2", "x", "08X", "-4o"] {
+            assert_eq!(FormatSpec::parse(input).unwrap().to_string(), *input);
+        }
+    }
+}
@@ -20,44 +20,37 @@ pub enum Symbol {
     SemiColon,
 }
 
+const SYMBOLS: &[(char, Symbol)] = &[
+    ('=', Symbol::Equal),
+    ('(', Symbol::LeftParen),
+    (')', Symbol::RightParen),
+    ('{', Symbol::LeftBrace),
+    ('}', Symbol::RightBrace),
+    (',', Symbol::Comma),
+    (':', Symbol::Colon),
+    (';', Symbol::SemiColon),
+];
+
 impl Symbol {
     pub(super) fn lex<'i>(input: &'i str, pos: &mut Position) -> Result<(&'i str, Token<'i>)> {
         let tpos = *pos;
-        if input.starts_with('=') {
-            pos.col += 1;
-
-            Ok((split(input, 1), Symbol::Equal.token(tpos)))
-        } else if input.starts_with('(') {
-            pos.col += 1;
-
-            Ok((split(input, 1), Symbol::LeftParen.token(tpos)))
-        } else if input.starts_with(')') {
-            pos.col += 1;
-
-            Ok((split(input, 1), Symbol::RightParen.token(tpos)))
-        } else if input.starts_with('{') {
-            pos.col += 1;
-
-            Ok((split(input, 1), Symbol::LeftBrace.token(tpos)))
-        } else if input.starts_with('}') {
-            pos.col += 1;
+        for (chr, symbol) in SYMBOLS {
+            if input.starts_with(*chr) {
+                pos.advance(*chr);
 
-            Ok((split(input, 1), Symbol::RightBrace.token(tpos)))
-        } else if input.starts_with(',') {
-            pos.col += 1;
-
-            Ok((split(input, 1), Symbol::Comma.token(tpos)))
-        } else if input.starts_with(':') {
-            pos.col += 1;
+                return Ok((split(input, chr.len_utf8()), symbol.token(tpos)));
+            }
+        }
 
-            Ok((split(input, 1), Symbol::Colon.token(tpos)))
-        } else if input.starts_with(';') {
-            pos.col += 1;
+        Err(Error::not_handled(tpos))
+    }
 
-            Ok((split(input, 1), Symbol::SemiColon.token(tpos)))
-        } else {
-            Err(Error::not_handled(tpos))
-        }
+    pub fn as_char(self) -> char {
+        SYMBOLS
+            .iter()
+            .find(|(_, symbol)| *symbol == self)
+            .map(|(chr, _)| *chr)
+            .unwrap()
     }
 
     fn token<'t>(self, pos: Position) -> Token<'t> {
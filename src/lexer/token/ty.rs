@@ -2,6 +2,7 @@ use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 
+use super::advance;
 use super::error::*;
 use super::split;
 use super::Position;
@@ -11,47 +12,52 @@ use super::TokenVariant;
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Ty {
     I32,
+    I64,
     Str,
+    StrList,
     Void,
 }
 
+pub(super) const TYS: &[(&str, Ty)] = &[
+    ("i32", Ty::I32),
+    ("i64", Ty::I64),
+    ("str", Ty::Str),
+    ("[str]", Ty::StrList),
+    ("void", Ty::Void),
+];
+
 impl<'t> Ty {
-    pub(super) fn lex(input: &'t str, pos: &mut Position) -> Result<(&'t str, Token<'t>)> {
-        let mut chars = input.chars();
-        let tpos = *pos;
-        if input.starts_with("i32") {
-            let next = chars.nth(3).unwrap(); // FIXME
-            if next.is_alphanumeric() || next == '_' {
-                return Err(Error::not_handled(tpos));
-            }
+    pub(super) fn from_ident(ident: &str) -> Option<Ty> {
+        TYS.iter()
+            .find(|(name, _)| *name == ident)
+            .map(|(_, ty)| *ty)
+    }
 
-            pos.col += 3;
+    pub(super) fn lex(input: &'t str, pos: &mut Position) -> Result<(&'t str, Token<'t>)> {
+        for (name, ty) in TYS {
+            if name.starts_with('[') && input.starts_with(name) {
+                let tpos = *pos;
+                advance(name, pos);
 
-            Ok((split(input, 3), Ty::I32.token(tpos)))
-        } else if input.starts_with("str") {
-            let next = chars.nth(3).unwrap(); // FIXME
-            if next.is_alphanumeric() || next == '_' {
-                return Err(Error::not_handled(tpos));
+                return Ok((split(input, name.len()), ty.token(tpos)));
             }
+        }
 
-            pos.col += 3;
-
-            Ok((split(input, 3), Ty::Str.token(tpos)))
-        } else if input.starts_with("void") {
-            let next = chars.nth(4).unwrap(); // FIXME
-            if next.is_alphanumeric() || next == '_' {
-                return Err(Error::not_handled(tpos));
-            }
+        Err(Error::not_handled(*pos))
+    }
 
-            pos.col += 4;
+    pub fn is_int(self) -> bool {
+        self == Ty::I32 || self == Ty::I64
+    }
 
-            Ok((split(input, 4), Ty::Void.token(tpos)))
-        } else {
-            Err(Error::not_handled(tpos))
-        }
+    pub fn as_str(self) -> &'static str {
+        TYS.iter()
+            .find(|(_, ty)| *ty == self)
+            .map(|(name, _)| *name)
+            .unwrap()
     }
 
-    fn token(self, pos: Position) -> Token<'t> {
+    pub(super) fn token(self, pos: Position) -> Token<'t> {
         Token {
             token: TokenVariant::Ty(self),
             pos,
@@ -64,7 +70,9 @@ impl Display for Ty {
         write!(fmt, "ty::")?;
         match self {
             Ty::I32 => write!(fmt, "i32"),
+            Ty::I64 => write!(fmt, "i64"),
             Ty::Str => write!(fmt, "str"),
+            Ty::StrList => write!(fmt, "[str]"),
             Ty::Void => write!(fmt, "void"),
         }
     }
@@ -0,0 +1,174 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use super::skip_whitespace;
+use super::Position;
+use super::Token;
+
+#[derive(Debug)]
+pub struct TriviaToken<'t> {
+    pub leading: Vec<Trivia<'t>>,
+    pub token: Token<'t>,
+    pub text: &'t str,
+    pub trailing: Vec<Trivia<'t>>,
+}
+
+#[derive(Debug)]
+pub enum Trivia<'t> {
+    Whitespace(&'t str),
+    Comment(&'t str),
+}
+
+// printing every token in order gives back the input byte for byte
+pub fn lex_trivia<'i>(mut input: &'i str) -> Vec<TriviaToken<'i>> {
+    let mut tokens: Vec<TriviaToken> = vec![];
+    let mut leading = vec![];
+    let mut trailing = false;
+
+    let mut pos = Position::default();
+    loop {
+        let rest = skip_whitespace(input, &mut pos);
+        let mut whitespace = &input[..input.len() - rest.len()];
+        input = rest;
+
+        if trailing && !whitespace.is_empty() {
+            let end = match whitespace.find('\n') {
+                Some(end) => {
+                    trailing = false;
+                    end + 1
+                }
+                None => whitespace.len(),
+            };
+
+            let last = tokens.last_mut().unwrap();
+            last.trailing.push(Trivia::Whitespace(&whitespace[..end]));
+            whitespace = &whitespace[end..];
+        }
+
+        if !whitespace.is_empty() {
+            leading.push(Trivia::Whitespace(whitespace));
+        }
+
+        let (rest, token) = Token::lex(input, &mut pos);
+        let text = &input[..input.len() - rest.len()];
+        input = rest;
+
+        match token {
+            Some(token) => {
+                let eof = token.is_eof();
+
+                tokens.push(TriviaToken {
+                    leading,
+                    token,
+                    text,
+                    trailing: vec![],
+                });
+
+                if eof {
+                    break;
+                }
+
+                leading = vec![];
+                trailing = true;
+            }
+            None if trailing => {
+                let last = tokens.last_mut().unwrap();
+                last.trailing.push(Trivia::Comment(text));
+                trailing = !text.contains('\n');
+            }
+            None => leading.push(Trivia::Comment(text)),
+        }
+    }
+
+    tokens
+}
+
+impl<'t> Display for TriviaToken<'t> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        for trivia in &self.leading {
+            write!(fmt, "{}", trivia)?;
+        }
+
+        write!(fmt, "{}", self.text)?;
+
+        for trivia in &self.trailing {
+            write!(fmt, "{}", trivia)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'t> Display for Trivia<'t> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Trivia::Whitespace(whitespace) => write!(fmt, "{}", whitespace),
+            Trivia::Comment(comment) => write!(fmt, "{}", comment),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    fn print(input: &str) -> String {
+        lex_trivia(input)
+            .iter()
+            .map(|token| token.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_examples() {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+
+        for entry in fs::read_dir(examples).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "kt") {
+                continue;
+            }
+
+            let input = fs::read_to_string(&path).unwrap();
+            assert_eq!(print(&input), input, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn round_trips_trivia_at_end_of_input() {
+        for input in &[
+            "",
+            "  \n\t",
+            "// no newline",
+            "let /* a */ x = 1; /* b /* nested */ */\n\n// c\n",
+            "func @ `unterminated",
+            "/* unterminated",
+        ] {
+            assert_eq!(&print(input), input);
+        }
+    }
+
+    #[test]
+    fn attaches_comments_to_the_nearest_token() {
+        let tokens = lex_trivia("// a\nlet x; // b\n/* c */ return");
+
+        match tokens[0].leading.as_slice() {
+            [Trivia::Comment("// a\n")] => (),
+            leading => panic!("{:?}", leading),
+        }
+
+        match tokens[2].trailing.as_slice() {
+            [Trivia::Whitespace(" "), Trivia::Comment("// b\n")] => (),
+            trailing => panic!("{:?}", trailing),
+        }
+
+        match tokens[3].leading.as_slice() {
+            [Trivia::Comment("/* c */"), Trivia::Whitespace(" ")] => (),
+            leading => panic!("{:?}", leading),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+
+pub type Result<OK> = std::result::Result<OK, Error>;
+
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    TempDir(PathBuf, io::Error),
+    MissingLinker(String),
+    Spawn(String, io::Error),
+    Failed(String, ExitStatus),
+    MissingRuntime(String),
+    Runtime(String, io::Error),
+    NotRunnable(String),
+}
+
+impl Error {
+    pub(super) fn temp_dir(dir: PathBuf, err: io::Error) -> Error {
+        Error {
+            kind: ErrorKind::TempDir(dir, err),
+        }
+    }
+
+    pub(super) fn missing_linker(linker: String) -> Error {
+        Error {
+            kind: ErrorKind::MissingLinker(linker),
+        }
+    }
+
+    pub(super) fn spawn(linker: String, err: io::Error) -> Error {
+        Error {
+            kind: ErrorKind::Spawn(linker, err),
+        }
+    }
+
+    pub(super) fn failed(linker: String, status: ExitStatus) -> Error {
+        Error {
+            kind: ErrorKind::Failed(linker, status),
+        }
+    }
+
+    pub(super) fn missing_runtime(runtime: String) -> Error {
+        Error {
+            kind: ErrorKind::MissingRuntime(runtime),
+        }
+    }
+
+    pub(super) fn runtime(runtime: String, err: io::Error) -> Error {
+        Error {
+            kind: ErrorKind::Runtime(runtime, err),
+        }
+    }
+
+    pub(super) fn not_runnable(triple: String) -> Error {
+        Error {
+            kind: ErrorKind::NotRunnable(triple),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::TempDir(dir, err) => write!(
+                fmt,
+                "couldn't create the temporary directory {}: {}",
+                dir.display(),
+                err
+            ),
+            ErrorKind::MissingLinker(linker) => write!(
+                fmt,
+                "linker `{}` not found (use `--linker` or set `CC` to pick another one)",
+                linker
+            ),
+            ErrorKind::Spawn(linker, err) => {
+                write!(fmt, "couldn't run linker `{}`: {}", linker, err)
+            }
+            ErrorKind::Failed(linker, status) => {
+                write!(fmt, "linker `{}` failed ({})", linker, status)
+            }
+            ErrorKind::MissingRuntime(runtime) => write!(
+                fmt,
+                "wasm runtime `{}` not found (set `SHEDDER_WASM_RUNTIME` to pick another one)",
+                runtime
+            ),
+            ErrorKind::Runtime(runtime, err) => {
+                write!(fmt, "couldn't run wasm runtime `{}`: {}", runtime, err)
+            }
+            ErrorKind::NotRunnable(triple) => write!(
+                fmt,
+                "can't run programs built for `{}` (only WASI targets have an entry point)",
+                triple
+            ),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::fs;
+use std::hash::BuildHasher;
+use std::hash::Hasher;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::process::Command;
+use std::time::SystemTime;
+
+mod error;
+
+pub use error::*;
+
+const DEFAULT_LINKER: &str = "cc";
+const DEFAULT_WASM_LINKER: &str = "wasm-ld";
+const DEFAULT_WASI_LINKER: &str = "clang";
+const DEFAULT_WASM_RUNTIME: &str = "wasmtime";
+const TEMP_DIR_ATTEMPTS: usize = 16;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Flavor {
+    Native,
+    Wasm,
+    Wasi,
+}
+
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempDir {
+    // the name can't be guessed and an existing directory is never reused,
+    // since `Drop` removes whatever is in it
+    pub fn new(keep: bool) -> Result<TempDir> {
+        let mut path = PathBuf::new();
+        for _ in 0..TEMP_DIR_ATTEMPTS {
+            let name = format!("shedder-{}-{:016x}", process::id(), random());
+            path = env::temp_dir().join(name);
+
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(TempDir { path, keep }),
+                Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => (),
+                Err(err) => return Err(Error::temp_dir(path, err)),
+            }
+        }
+
+        Err(Error::temp_dir(path, io::ErrorKind::AlreadyExists.into()))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// `RandomState` is seeded by the OS, mixing in the time keeps two states
+// created close together apart
+fn random() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(time) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(time.as_nanos());
+    }
+
+    hasher.finish()
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if self.keep {
+            eprintln!("intermediate files kept in {}", self.path.display());
+        } else {
+            fs::remove_dir_all(&self.path).ok();
+        }
+    }
+}
+
+impl Flavor {
+    pub fn from_triple(triple: &str) -> Flavor {
+        if !triple.starts_with("wasm") {
+            Flavor::Native
+        } else if triple.ends_with("wasi") {
+            Flavor::Wasi
+        } else {
+            Flavor::Wasm
+        }
+    }
+
+    pub fn executable(self, output: PathBuf) -> PathBuf {
+        match self {
+            Flavor::Native => output,
+            Flavor::Wasm | Flavor::Wasi => output.with_extension("wasm"),
+        }
+    }
+
+    fn linker(self) -> String {
+        match self {
+            Flavor::Native => env::var("CC").unwrap_or_else(|_| DEFAULT_LINKER.into()),
+            Flavor::Wasm => DEFAULT_WASM_LINKER.into(),
+            Flavor::Wasi => env::var("CC").unwrap_or_else(|_| DEFAULT_WASI_LINKER.into()),
+        }
+    }
+
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            Flavor::Native => &[],
+            Flavor::Wasm => &["--no-entry", "--export=main"],
+            Flavor::Wasi => &["--target=wasm32-wasi"],
+        }
+    }
+}
+
+pub fn link(
+    objects: &[PathBuf],
+    output: &Path,
+    linker: Option<&str>,
+    flavor: Flavor,
+) -> Result<()> {
+    let linker = match linker {
+        Some(linker) => linker.into(),
+        None => flavor.linker(),
+    };
+
+    let status = Command::new(&linker)
+        .args(flavor.args())
+        .args(objects)
+        .arg("-o")
+        .arg(output)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(Error::failed(linker, status)),
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => Err(Error::missing_linker(linker)),
+        Err(err) => Err(Error::spawn(linker, err)),
+    }
+}
+
+pub fn wasm_runtime(triple: &str) -> Result<String> {
+    if Flavor::from_triple(triple) != Flavor::Wasi {
+        return Err(Error::not_runnable(triple.into()));
+    }
+
+    Ok(env::var("SHEDDER_WASM_RUNTIME").unwrap_or_else(|_| DEFAULT_WASM_RUNTIME.into()))
+}
+
+pub fn run_wasm(runtime: String, module: &Path, args: &[String]) -> Result<i32> {
+    let status = Command::new(&runtime).arg(module).args(args).status();
+
+    match status {
+        Ok(status) => Ok(status.code().unwrap_or(1)),
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+            Err(Error::missing_runtime(runtime))
+        }
+        Err(err) => Err(Error::runtime(runtime, err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn makes_a_new_dir_every_time() {
+        let first = TempDir::new(false).unwrap();
+        let second = TempDir::new(false).unwrap();
+
+        assert_ne!(first.path(), second.path());
+        assert!(first.path().is_dir());
+        assert!(second.path().is_dir());
+    }
+
+    #[test]
+    fn removes_the_dir_when_dropped() {
+        let dir = TempDir::new(false).unwrap();
+        let path = dir.path().to_path_buf();
+        drop(dir);
+
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,336 @@
+use std::fmt::Display;
+
+use serde_json::json;
+use serde_json::Value;
+
+use crate::ast::Tree;
+use crate::dependencies::Graph;
+use crate::lexer::lex;
+use crate::lexer::lex_recover;
+use crate::lexer::DynStringSeg;
+use crate::lexer::Ident;
+use crate::lexer::Keyword;
+use crate::lexer::Literal;
+use crate::lexer::Position;
+use crate::lexer::Symbol;
+use crate::lexer::Token;
+use crate::lexer::TokenVariant;
+use crate::lexer::Ty;
+use crate::parser::decl::Arg;
+use crate::parser::parse;
+use crate::parser::stmt::Stmt;
+
+use super::position;
+
+const SEVERITY_ERROR: u64 = 1;
+
+const SYMBOL_FUNCTION: u64 = 12;
+const SYMBOL_VARIABLE: u64 = 13;
+
+const COMPLETION_FUNCTION: u64 = 3;
+const COMPLETION_VARIABLE: u64 = 6;
+
+pub(super) fn diagnostics(input: &str) -> Vec<Value> {
+    let tokens = match lex(input) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            return err
+                .errors()
+                .into_iter()
+                .map(|err| diagnostic(input, err.pos(), err))
+                .collect();
+        }
+    };
+
+    let module = match parse(&tokens) {
+        Ok(module) => module,
+        Err(err) => return vec![diagnostic(input, err.pos(), &err)],
+    };
+
+    let mut ast = Tree::build(&[&module]);
+    ast.declare_builtins();
+    ast.infer();
+
+    let dependencies = Graph::build(&ast);
+    if let Err(err) = dependencies.verify() {
+        return err
+            .errors()
+            .into_iter()
+            .map(|err| diagnostic(input, err.pos(), err))
+            .collect();
+    }
+
+    match ast.verify() {
+        Ok(()) => vec![],
+        Err(err) => err
+            .errors()
+            .into_iter()
+            .map(|err| match err.span() {
+                Some((start, end)) => span_diagnostic(input, start, end, err),
+                None => diagnostic(input, err.pos(), err),
+            })
+            .collect(),
+    }
+}
+
+pub(super) fn definition(input: &str, offset: usize) -> Option<Position> {
+    let tokens = lex(input).ok()?;
+    let module = parse(&tokens).ok()?;
+
+    let (ident, call) = ident_at(&tokens, offset)?;
+    if call {
+        return module
+            .funcs
+            .iter()
+            .find(|func| func.name == *ident)
+            .map(|func| func.pos);
+    }
+
+    let func = module
+        .funcs
+        .iter()
+        .find(|func| func.pos.offset <= offset && offset <= func.end.offset)?;
+
+    let let_ = func.stmts.iter().rev().find_map(|stmt| match stmt {
+        Stmt::Let(let_) if let_.pos.offset <= offset && let_.name == *ident => Some(let_.pos),
+        _ => None,
+    });
+
+    let_.or_else(|| {
+        func.args
+            .iter()
+            .find(|arg| arg.name == *ident)
+            .map(|arg| arg.pos)
+    })
+}
+
+pub(super) fn hover(input: &str, offset: usize) -> Option<Value> {
+    let tokens = lex(input).ok()?;
+    let module = parse(&tokens).ok()?;
+
+    let mut ast = Tree::build(&[&module]);
+    ast.declare_builtins();
+
+    let (ident, call) = ident_at(&tokens, offset)?;
+    if !call {
+        return None;
+    }
+
+    let func = ast.funcs.get(ident)?;
+
+    Some(json!({
+        "contents": {
+            "kind": "markdown",
+            "value": format!("```\n{}\n```", signature(&func.name, func.args, func.ret, func.variadic)),
+        },
+    }))
+}
+
+pub(super) fn symbols(input: &str) -> Vec<Value> {
+    let tokens = match lex(input) {
+        Ok(tokens) => tokens,
+        Err(_) => return vec![],
+    };
+
+    let module = match parse(&tokens) {
+        Ok(module) => module,
+        Err(_) => return vec![],
+    };
+
+    module
+        .funcs
+        .iter()
+        .map(|func| {
+            let lets = func
+                .stmts
+                .iter()
+                .filter_map(|stmt| match stmt {
+                    Stmt::Let(let_) => Some(json!({
+                        "name": let_.name.inner(),
+                        "kind": SYMBOL_VARIABLE,
+                        "range": position::word(input, let_.pos.offset),
+                        "selectionRange": position::word(input, let_.pos.offset),
+                    })),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            json!({
+                "name": func.name.inner(),
+                "detail": signature(&func.name, &func.args, func.ret, func.variadic),
+                "kind": SYMBOL_FUNCTION,
+                "range": position::range(input, func.pos.offset, func.end.offset + 1),
+                "selectionRange": position::word(input, func.pos.offset),
+                "children": lets,
+            })
+        })
+        .collect()
+}
+
+pub(super) fn completion(input: &str, offset: usize) -> Vec<Value> {
+    let tokens = lex_recover(input);
+
+    let mut builtins = Tree::build(&[]);
+    builtins.declare_builtins();
+
+    let mut items = builtins
+        .funcs
+        .values()
+        .map(|func| {
+            json!({
+                "label": func.name.inner(),
+                "kind": COMPLETION_FUNCTION,
+                "detail": signature(&func.name, func.args, func.ret, func.variadic),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut funcs = vec![];
+    let mut locals = vec![];
+    let mut depth = 0usize;
+
+    for (t, token) in tokens.iter().enumerate() {
+        let before = token.pos.offset < offset;
+        let next = tokens.get(t + 1).map(|token| &token.token);
+
+        match (&token.token, next) {
+            (TokenVariant::Keyword(Keyword::Func), Some(TokenVariant::Ident(name))) => {
+                funcs.push(name.inner());
+
+                if before {
+                    locals.clear();
+                }
+            }
+            (TokenVariant::Keyword(Keyword::Let), Some(TokenVariant::Ident(name))) if before => {
+                locals.push(name.inner());
+            }
+            (TokenVariant::Ident(name), Some(TokenVariant::Symbol(Symbol::Colon)))
+                if before && depth == 0 =>
+            {
+                locals.push(name.inner());
+            }
+            (TokenVariant::Symbol(Symbol::LeftBrace), _) => depth += 1,
+            (TokenVariant::Symbol(Symbol::RightBrace), _) => {
+                depth = depth.saturating_sub(1);
+
+                if before && depth == 0 {
+                    locals.clear();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    for func in funcs {
+        if !builtins.funcs.contains_key(&Ident::Ref(func)) {
+            items.push(json!({
+                "label": func,
+                "kind": COMPLETION_FUNCTION,
+            }));
+        }
+    }
+
+    locals.sort_unstable();
+    locals.dedup();
+    for local in locals {
+        items.push(json!({
+            "label": local,
+            "kind": COMPLETION_VARIABLE,
+        }));
+    }
+
+    items
+}
+
+fn diagnostic(input: &str, pos: Option<Position>, message: &dyn Display) -> Value {
+    let range = position::word(input, pos.unwrap_or_default().offset);
+    ranged_diagnostic(range, message)
+}
+
+fn span_diagnostic(input: &str, start: Position, end: Position, message: &dyn Display) -> Value {
+    let range = position::range(input, start.offset, end.offset + 1);
+    ranged_diagnostic(range, message)
+}
+
+fn ranged_diagnostic(range: Value, message: &dyn Display) -> Value {
+    json!({
+        "range": range,
+        "severity": SEVERITY_ERROR,
+        "source": "shedder",
+        "message": message.to_string(),
+    })
+}
+
+fn ident_at<'t>(tokens: &'t [Token<'t>], offset: usize) -> Option<(&'t Ident<'t>, bool)> {
+    for (t, token) in tokens.iter().enumerate() {
+        match &token.token {
+            TokenVariant::Ident(ident)
+                if token.pos.offset <= offset
+                    && offset <= token.pos.offset + ident.inner().len() =>
+            {
+                let call = tokens
+                    .get(t + 1)
+                    .is_some_and(|token| token.eq_symbol(Symbol::LeftParen));
+                return Some((ident, call));
+            }
+            TokenVariant::Literal(Literal::DynString(segs)) => {
+                for seg in segs {
+                    if let DynStringSeg::Insert(tokens, ..) = seg {
+                        if let Some(ident) = ident_at(tokens, offset) {
+                            return Some(ident);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+fn signature(name: &Ident, args: &[Arg], ret: Ty, variadic: bool) -> String {
+    let mut args = args
+        .iter()
+        .map(|arg| format!("{}: {}", arg.name.inner(), arg.ty.as_str()))
+        .collect::<Vec<_>>();
+    if variadic {
+        args.push("...".into());
+    }
+
+    format!(
+        "func {}({}): {}",
+        name.inner(),
+        args.join(", "),
+        ret.as_str()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(items: &[Value]) -> Vec<&str> {
+        items
+            .iter()
+            .filter(|item| item["kind"] == COMPLETION_VARIABLE)
+            .map(|item| item["label"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn completes_each_local_once() {
+        let input = "func f(a: i32) { let b = a; let a = b; let b = 1;  }";
+        let offset = input.rfind(' ').unwrap();
+
+        assert_eq!(labels(&completion(input, offset)), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn completes_only_locals_of_the_enclosing_func() {
+        let input = "func f(a: i32) { let b = a; }\nfunc g() { let c = 1;  }";
+        let offset = input.rfind(' ').unwrap();
+
+        assert_eq!(labels(&completion(input, offset)), vec!["c"]);
+    }
+}
@@ -0,0 +1,167 @@
+use std::io;
+
+use fnv::FnvHashMap;
+use serde_json::json;
+use serde_json::Value;
+
+mod analysis;
+mod position;
+mod rpc;
+
+const TEXT_DOCUMENT_SYNC_FULL: u64 = 1;
+
+#[derive(Default, Debug)]
+struct Server {
+    documents: FnvHashMap<String, String>,
+    shutdown: bool,
+    exit: bool,
+}
+
+pub fn run() -> io::Result<bool> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut input = stdin.lock();
+    let mut output = stdout.lock();
+
+    let mut server = Server::default();
+    while let Some(content) = rpc::read_message(&mut input)? {
+        let messages = match serde_json::from_slice(&content) {
+            Ok(message) => server.handle(message),
+            Err(err) => vec![rpc::error(Value::Null, rpc::PARSE_ERROR, &err.to_string())],
+        };
+
+        for message in &messages {
+            rpc::write_message(&mut output, message)?;
+        }
+
+        if server.exit {
+            break;
+        }
+    }
+
+    Ok(server.shutdown)
+}
+
+impl Server {
+    fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = match message["method"].as_str() {
+            Some(method) => method,
+            None => return vec![],
+        };
+
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+
+        match (method, id) {
+            ("initialize", Some(id)) => vec![rpc::response(
+                id,
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                        "definitionProvider": true,
+                        "hoverProvider": true,
+                        "documentSymbolProvider": true,
+                        "completionProvider": {},
+                    },
+                    "serverInfo": {
+                        "name": "shedder",
+                    },
+                }),
+            )],
+            ("shutdown", Some(id)) => {
+                self.shutdown = true;
+                vec![rpc::response(id, Value::Null)]
+            }
+            ("exit", None) => {
+                self.exit = true;
+                vec![]
+            }
+            ("textDocument/didOpen", None) => {
+                let document = &params["textDocument"];
+                match (document["uri"].as_str(), document["text"].as_str()) {
+                    (Some(uri), Some(text)) => self.open(uri, text.into()),
+                    _ => vec![],
+                }
+            }
+            ("textDocument/didChange", None) => {
+                let uri = params["textDocument"]["uri"].as_str();
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+
+                match (uri, text) {
+                    (Some(uri), Some(text)) => self.open(uri, text.into()),
+                    _ => vec![],
+                }
+            }
+            ("textDocument/didClose", None) => match params["textDocument"]["uri"].as_str() {
+                Some(uri) => {
+                    self.documents.remove(uri);
+                    vec![publish_diagnostics(uri, vec![])]
+                }
+                None => vec![],
+            },
+            ("textDocument/definition", Some(id)) => {
+                let result = self.query(params, |uri, input, offset| {
+                    analysis::definition(input, offset).map(|pos| {
+                        json!({
+                            "uri": uri,
+                            "range": position::word(input, pos.offset),
+                        })
+                    })
+                });
+
+                vec![rpc::response(id, result.unwrap_or(Value::Null))]
+            }
+            ("textDocument/hover", Some(id)) => {
+                let result = self.query(params, |_, input, offset| analysis::hover(input, offset));
+                vec![rpc::response(id, result.unwrap_or(Value::Null))]
+            }
+            ("textDocument/documentSymbol", Some(id)) => {
+                let result = self.query(params, |_, input, _| Some(analysis::symbols(input)));
+                vec![rpc::response(id, json!(result.unwrap_or_default()))]
+            }
+            ("textDocument/completion", Some(id)) => {
+                let result = self.query(params, |_, input, offset| {
+                    Some(analysis::completion(input, offset))
+                });
+                vec![rpc::response(id, json!(result.unwrap_or_default()))]
+            }
+            (method, Some(id)) => vec![rpc::error(
+                id,
+                rpc::METHOD_NOT_FOUND,
+                &format!("unhandled method {}", method),
+            )],
+            (_, None) => vec![],
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: String) -> Vec<Value> {
+        let diagnostics = analysis::diagnostics(&text);
+        self.documents.insert(uri.into(), text);
+
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+
+    fn query<T, F>(&self, params: &Value, query: F) -> Option<T>
+    where
+        F: FnOnce(&str, &str, usize) -> Option<T>,
+    {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let input = self.documents.get(uri)?;
+        let offset = position::from_lsp(input, &params["position"]).unwrap_or(0);
+
+        query(uri, input, offset)
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    rpc::notification(
+        "textDocument/publishDiagnostics",
+        json!({
+            "uri": uri,
+            "diagnostics": diagnostics,
+        }),
+    )
+}
@@ -0,0 +1,58 @@
+use serde_json::json;
+use serde_json::Value;
+
+pub(super) fn to_lsp(input: &str, offset: usize) -> Value {
+    let offset = offset.min(input.len());
+    let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+
+    json!({
+        "line": input[..offset].matches('\n').count(),
+        "character": input[start..offset].encode_utf16().count(),
+    })
+}
+
+pub(super) fn from_lsp(input: &str, position: &Value) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+
+    let mut start = 0;
+    for _ in 0..line {
+        start += input[start..].find('\n')? + 1;
+    }
+
+    let mut units = 0;
+    for (i, chr) in input[start..].char_indices() {
+        if units >= character || chr == '\n' {
+            return Some(start + i);
+        }
+
+        units += chr.len_utf16();
+    }
+
+    Some(input.len())
+}
+
+pub(super) fn range(input: &str, start: usize, end: usize) -> Value {
+    json!({
+        "start": to_lsp(input, start),
+        "end": to_lsp(input, end),
+    })
+}
+
+pub(super) fn word(input: &str, offset: usize) -> Value {
+    let offset = offset.min(input.len());
+    let rest = &input[offset..];
+
+    let mut len = rest
+        .find(|chr: char| !chr.is_alphanumeric() && chr != '_')
+        .unwrap_or(rest.len());
+    if len == 0 {
+        len = rest
+            .chars()
+            .next()
+            .filter(|chr| *chr != '\n')
+            .map_or(0, |chr| chr.len_utf8());
+    }
+
+    range(input, offset, offset + len)
+}
@@ -0,0 +1,84 @@
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+use serde_json::json;
+use serde_json::Value;
+
+pub(super) const PARSE_ERROR: i64 = -32700;
+pub(super) const METHOD_NOT_FOUND: i64 = -32601;
+
+pub(super) fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        let mut header = line.splitn(2, ':');
+        if let (Some(name), Some(value)) = (header.next(), header.next()) {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = Some(
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+                );
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+
+    Ok(Some(content))
+}
+
+pub(super) fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+pub(super) fn response(id: Value, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result,
+    })
+}
+
+pub(super) fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code,
+            "message": message,
+        },
+    })
+}
+
+pub(super) fn notification(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+    })
+}
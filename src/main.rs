@@ -1,30 +1,145 @@
+use std::fmt::Display;
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
+use std::process;
 
+#[cfg(feature = "llvm")]
 use inkwell::targets::InitializationConfig;
+#[cfg(feature = "llvm")]
 use inkwell::targets::Target;
 use structopt::StructOpt;
 
 mod ast;
 mod cli;
+#[cfg(feature = "llvm")]
 mod compiler;
 mod dependencies;
+mod formatter;
+mod interpreter;
 mod lexer;
+#[cfg(feature = "llvm")]
+mod linker;
+mod lsp;
 mod parser;
+mod repl;
 
+#[cfg(feature = "llvm")]
+use cli::BuildOpts;
+use cli::Command;
 use cli::Opt;
+#[cfg(feature = "llvm")]
 use compiler::compile;
+#[cfg(feature = "llvm")]
+use compiler::Emit;
+#[cfg(feature = "llvm")]
+use compiler::OptLevel;
+#[cfg(feature = "llvm")]
+use compiler::TargetSpec;
+use interpreter::Interpreter;
 use lexer::lex;
-use lexer::Ident;
-use lexer::Ty;
-use parser::decl::Arg;
 use parser::parse;
 
 fn main() {
     let opt = Opt::from_args();
-    println!("file: {:?}", opt.file);
 
-    let input = match fs::read_to_string(opt.file) {
+    match opt.cmd {
+        #[cfg(feature = "llvm")]
+        Some(Command::Compile { tab_width, file }) => compile_file(&file, tab_width),
+        #[cfg(feature = "llvm")]
+        Some(Command::Build {
+            tab_width,
+            file,
+            opts,
+        }) => build_file(&file, tab_width, &opts),
+        Some(Command::Run {
+            interpret,
+            tab_width,
+            target,
+            file,
+            args,
+        }) => run_file(&file, tab_width, interpret, target.as_ref(), &args),
+        Some(Command::Fmt { check, files }) => format_files(&files, check),
+        Some(Command::Lsp) => serve_lsp(),
+        Some(Command::Repl) => start_repl(),
+        None => match opt.file {
+            Some(file) => compile_default(&file, opt.tab_width),
+            None => {
+                Opt::clap().print_help().unwrap();
+                println!();
+                process::exit(1);
+            }
+        },
+    }
+}
+
+#[cfg(feature = "llvm")]
+fn compile_default(file: &Path, tab_width: usize) {
+    compile_file(file, tab_width)
+}
+
+#[cfg(not(feature = "llvm"))]
+fn compile_default(file: &Path, _: usize) {
+    eprintln!(
+        "{}: shedder was built without LLVM, use `shedder run` to interpret it",
+        file.display()
+    );
+    process::exit(1);
+}
+
+fn start_repl() {
+    if let Err(err) = repl::run() {
+        panic!("{}", err);
+    }
+}
+
+fn serve_lsp() {
+    match lsp::run() {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(err) => panic!("{}", err),
+    }
+}
+
+fn format_files(files: &[PathBuf], check: bool) {
+    let mut failed = false;
+
+    for file in files {
+        let input = match fs::read_to_string(file) {
+            Ok(input) => input,
+            Err(err) => panic!("{}", err),
+        };
+
+        match formatter::format(&input) {
+            Ok(output) => {
+                if output == input {
+                    continue;
+                }
+
+                if check {
+                    println!("{}: not formatted", file.display());
+                    failed = true;
+                } else if let Err(err) = fs::write(file, output) {
+                    panic!("{}", err);
+                }
+            }
+            Err(err) => {
+                eprintln!("{}: {}", file.display(), err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+#[cfg(feature = "llvm")]
+fn compile_file(file: &Path, tab_width: usize) {
+    println!("file: {:?}", file);
+
+    let input = match fs::read_to_string(file) {
         Ok(input) => input,
         Err(err) => panic!("{}", err),
     };
@@ -36,7 +151,10 @@ fn main() {
     println!("-----------------");
     println!();
 
-    let tokens = lex(&input).unwrap();
+    let tokens = match lex(&input) {
+        Ok(tokens) => tokens,
+        Err(err) => lex_failed(file, &input, &err, tab_width),
+    };
 
     println!("- tokens:  ");
     for token in &tokens {
@@ -84,29 +202,8 @@ fn main() {
     println!();
 
     let mut ast = ast::Tree::build(&[&main]);
-
-    // --- FIXME ---
-    let printf = Ident::Owned("printf".into());
-    let printf_args = vec![Arg {
-        name: Ident::Owned("printf".into()),
-        ty: Ty::Str,
-    }];
-
-    ast.funcs.insert(
-        printf.as_ref(),
-        ast::Func {
-            name: printf.as_ref(),
-            args: &printf_args,
-            ret: lexer::Ty::Void,
-            variadic: true,
-            start: None,
-        },
-    );
-
-    for module in ast.modules.values_mut() {
-        module.funcs.insert(printf.as_ref());
-    }
-    // --- FIXME ---
+    ast.declare_builtins();
+    ast.infer();
 
     println!("{}", ast);
 
@@ -133,13 +230,7 @@ fn main() {
     ast.verify().unwrap();
     println!("AST: OK");
 
-    // --- FIXME ---
-    for module in ast.modules.values_mut() {
-        module.funcs.remove(&printf);
-    }
-
-    ast.funcs.remove(&printf);
-    // --- FIXME ---
+    ast.remove_builtins();
 
     println!();
     println!("-----------------");
@@ -148,11 +239,270 @@ fn main() {
     println!("-----------------");
     println!();
 
-    Target::initialize_x86(&InitializationConfig::default());
+    let spec = TargetSpec::new(None, None, &[]).unwrap();
 
-    let mut compiled = compile(&ast).unwrap();
+    let compiled = compile(&ast, file, false, OptLevel::O0, &spec).unwrap();
     println!("{}", compiled);
 
-    compiled.create_target_machine();
-    compiled.write_to_file(&Path::new("out.o")).unwrap();
+    let dir = linker::TempDir::new(true).unwrap();
+    let object = dir
+        .path()
+        .join(file.with_extension("o").file_name().unwrap());
+
+    compiled.write_to_file(&object, Emit::Obj).unwrap();
+    println!("OBJECT: {}", object.display());
+}
+
+#[cfg(feature = "llvm")]
+fn build_file(file: &Path, tab_width: usize, opts: &BuildOpts) {
+    let spec = match TargetSpec::new(
+        opts.target.as_deref(),
+        opts.target_cpu.as_deref(),
+        &opts.target_features,
+    ) {
+        Ok(spec) => spec,
+        Err(err) => failed(file, &err),
+    };
+
+    let flavor = linker::Flavor::from_triple(spec.triple());
+    let output = match &opts.output {
+        Some(output) => output.clone(),
+        None => flavor.executable(PathBuf::from(
+            file.file_stem().unwrap_or_else(|| "a.out".as_ref()),
+        )),
+    };
+
+    verified(file, tab_width, |mut ast| {
+        ast.remove_builtins();
+
+        let compiled = match compile(&ast, file, opts.debug, opts.opt, &spec) {
+            Ok(compiled) => compiled,
+            Err(err) => failed(file, &err),
+        };
+
+        if let (Some(module), true) = (&opts.wasm_import_module, flavor != linker::Flavor::Native) {
+            compiled.set_import_module(module);
+        }
+
+        let passes = compiled.optimize(opts.opt);
+        if opts.print_passes {
+            for pass in passes {
+                eprintln!("pass: {}", pass);
+            }
+        }
+
+        if !opts.emit.is_empty() {
+            for emit in &opts.emit {
+                let path = match &opts.output {
+                    Some(output) if opts.emit.len() == 1 => output.clone(),
+                    _ => output.with_extension(emit.extension()),
+                };
+
+                if let Err(err) = compiled.write_to_file(&path, *emit) {
+                    failed(file, &err);
+                }
+            }
+
+            return;
+        }
+
+        let dir = match linker::TempDir::new(opts.keep_intermediates) {
+            Ok(dir) => dir,
+            Err(err) => failed(file, &err),
+        };
+
+        let linker = opts.linker.as_deref();
+        let linked = link_executable(&compiled, &dir, &output, linker, flavor);
+
+        // exiting skips destructors, so the intermediates are cleaned up first
+        drop(dir);
+
+        if let Err(err) = linked {
+            failed(file, &*err);
+        }
+    });
+}
+
+#[cfg(feature = "llvm")]
+fn link_executable(
+    compiled: &compiler::Compiled,
+    dir: &linker::TempDir,
+    output: &Path,
+    linker: Option<&str>,
+    flavor: linker::Flavor,
+) -> Result<(), Box<dyn Display>> {
+    let object = dir
+        .path()
+        .join(output.with_extension("o").file_name().unwrap());
+
+    if let Err(err) = compiled.write_to_file(&object, Emit::Obj) {
+        return Err(Box::new(err));
+    }
+
+    let runtime = dir.path().join("kitty_runtime.o");
+    if let Err(err) = compiled.write_runtime_to_file(&runtime) {
+        return Err(Box::new(err));
+    }
+
+    match linker::link(&[object, runtime], output, linker, flavor) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+fn run_file(
+    file: &Path,
+    tab_width: usize,
+    interpret: bool,
+    target: Option<&String>,
+    args: &[String],
+) {
+    let mut argv = vec![file.display().to_string()];
+    argv.extend_from_slice(args);
+
+    let code = verified(file, tab_width, |ast| match target {
+        Some(target) => execute_target(file, ast, target, &argv),
+        None => execute(file, ast, interpret, &argv),
+    });
+    process::exit(code);
+}
+
+fn verified<T, F: FnOnce(ast::Tree) -> T>(file: &Path, tab_width: usize, then: F) -> T {
+    let input = match fs::read_to_string(file) {
+        Ok(input) => input,
+        Err(err) => panic!("{}", err),
+    };
+
+    let tokens = match lex(&input) {
+        Ok(tokens) => tokens,
+        Err(err) => lex_failed(file, &input, &err, tab_width),
+    };
+
+    let main = match parse(&tokens) {
+        Ok(entry) => entry,
+        Err(err) => failed(file, &err),
+    };
+
+    let mut ast = ast::Tree::build(&[&main]);
+    ast.declare_builtins();
+    ast.infer();
+
+    if let Err(err) = dependencies::Graph::build(&ast).verify() {
+        failed(file, &err);
+    }
+
+    if let Err(err) = ast.verify() {
+        failed(file, &err);
+    }
+
+    then(ast)
+}
+
+#[cfg(feature = "llvm")]
+fn execute(file: &Path, mut ast: ast::Tree, interpret: bool, argv: &[String]) -> i32 {
+    if interpret {
+        return interpret_ast(file, &ast, argv);
+    }
+
+    ast.remove_builtins();
+
+    if let Err(err) = Target::initialize_native(&InitializationConfig::default()) {
+        failed(file, &err);
+    }
+
+    let spec = match TargetSpec::new(None, None, &[]) {
+        Ok(spec) => spec,
+        Err(err) => failed(file, &err),
+    };
+
+    let compiled = match compile(&ast, file, false, OptLevel::O0, &spec) {
+        Ok(compiled) => compiled,
+        Err(err) => failed(file, &err),
+    };
+
+    match compiled.run(argv) {
+        Ok(code) => code,
+        Err(err) => failed(file, &err),
+    }
+}
+
+#[cfg(not(feature = "llvm"))]
+fn execute(file: &Path, ast: ast::Tree, _: bool, argv: &[String]) -> i32 {
+    interpret_ast(file, &ast, argv)
+}
+
+#[cfg(feature = "llvm")]
+fn execute_target(file: &Path, mut ast: ast::Tree, target: &str, argv: &[String]) -> i32 {
+    let spec = match TargetSpec::new(Some(target), None, &[]) {
+        Ok(spec) => spec,
+        Err(err) => failed(file, &err),
+    };
+
+    let flavor = linker::Flavor::from_triple(spec.triple());
+    let runtime = match linker::wasm_runtime(spec.triple()) {
+        Ok(runtime) => runtime,
+        Err(err) => failed(file, &err),
+    };
+
+    ast.remove_builtins();
+
+    let compiled = match compile(&ast, file, false, OptLevel::O0, &spec) {
+        Ok(compiled) => compiled,
+        Err(err) => failed(file, &err),
+    };
+
+    let dir = match linker::TempDir::new(false) {
+        Ok(dir) => dir,
+        Err(err) => failed(file, &err),
+    };
+
+    let stem = file.file_stem().unwrap_or_else(|| "a.out".as_ref());
+    let module = flavor.executable(dir.path().join(stem));
+
+    if let Err(err) = link_executable(&compiled, &dir, &module, None, flavor) {
+        drop(dir);
+        failed(file, &*err);
+    }
+
+    let ran = linker::run_wasm(runtime, &module, &argv[1..]);
+
+    drop(dir);
+
+    match ran {
+        Ok(code) => code,
+        Err(err) => failed(file, &err),
+    }
+}
+
+#[cfg(not(feature = "llvm"))]
+fn execute_target(file: &Path, _: ast::Tree, target: &str, _: &[String]) -> i32 {
+    let err = format!("running for target `{}` needs the llvm backend", target);
+    failed(file, &err)
+}
+
+fn interpret_ast(file: &Path, ast: &ast::Tree, argv: &[String]) -> i32 {
+    match Interpreter::new(ast).run(argv) {
+        Ok(code) => code,
+        Err(err) => failed(file, &err),
+    }
+}
+
+fn lex_failed(file: &Path, input: &str, err: &lexer::Error, tab_width: usize) -> ! {
+    for err in err.errors() {
+        let pos = err.pos().unwrap();
+        eprintln!(
+            "{}:{}:{}: {}",
+            file.display(),
+            pos.line + 1,
+            pos.display_col(input, tab_width) + 1,
+            err
+        );
+    }
+
+    process::exit(1);
+}
+
+fn failed(file: &Path, err: &dyn Display) -> ! {
+    eprintln!("{}: {}", file.display(), err);
+    process::exit(1);
 }
@@ -4,6 +4,7 @@ use std::fmt::Formatter;
 
 use crate::lexer::Ident;
 use crate::lexer::Keyword;
+use crate::lexer::Position;
 use crate::lexer::Symbol;
 use crate::lexer::Token;
 use crate::lexer::TokenTy;
@@ -25,6 +26,8 @@ pub enum Decl<'d> {
 #[derive(Debug)]
 pub struct Func<'f> {
     pub name: Ident<'f>,
+    pub pos: Position,
+    pub end: Position,
     pub args: Vec<Arg<'f>>,
     pub ret: Ty,
     pub variadic: bool,
@@ -34,6 +37,7 @@ pub struct Func<'f> {
 #[derive(Debug)]
 pub struct Arg<'a> {
     pub name: Ident<'a>,
+    pub pos: Position,
     pub ty: Ty,
 }
 
@@ -44,7 +48,7 @@ impl<'d> Decl<'d> {
         handled
     }
 
-    pub(super) fn parse(tokens: &'d [Token<'d>]) -> Result<(usize, Decl<'d>)> {
+    pub(super) fn parse(tokens: &'d [Token<'d>]) -> Result<'d, (usize, Decl<'d>)> {
         if tokens.is_empty() {
             return Err(Error::missing_token(Self::handled(), None));
         }
@@ -65,7 +69,7 @@ impl<'f> Func<'f> {
         vec![TokenTy::Keyword(Keyword::Func)]
     }
 
-    fn parse(tokens: &'f [Token<'f>]) -> Result<(usize, Func<'f>)> {
+    fn parse(tokens: &'f [Token<'f>]) -> Result<'f, (usize, Func<'f>)> {
         try_eq_keyword(tokens, 0, Keyword::Func)?;
 
         let mut t = 1;
@@ -75,6 +79,7 @@ impl<'f> Func<'f> {
                 err
             })?
             .as_ref();
+        let pos = tokens[t].pos;
 
         t += 1;
         try_eq_symbol(tokens, t, Symbol::LeftParen).map_err(|mut err| {
@@ -101,14 +106,15 @@ impl<'f> Func<'f> {
             }
 
             if arg.is_none() {
-                arg = Some(
+                arg = Some((
                     try_get_ident(tokens, t)
                         .map_err(|mut err| {
                             err.max_after(tokens.get(t - 1).map(|token| token.pos));
                             err
                         })?
                         .as_ref(),
-                );
+                    tokens[t].pos,
+                ));
             } else {
                 try_eq_symbol(tokens, t, Symbol::Colon).map_err(|mut err| {
                     err.max_after(tokens.get(t - 1).map(|token| token.pos));
@@ -121,10 +127,8 @@ impl<'f> Func<'f> {
                     err
                 })?;
 
-                args.push(Arg {
-                    name: arg.unwrap(),
-                    ty,
-                });
+                let (name, pos) = arg.unwrap();
+                args.push(Arg { name, pos, ty });
                 arg = None;
             }
 
@@ -156,6 +160,7 @@ impl<'f> Func<'f> {
         })?;
 
         let mut stmts = vec![];
+        let end;
 
         t += 1;
         loop {
@@ -169,6 +174,7 @@ impl<'f> Func<'f> {
             }
 
             if tokens[t].eq_symbol(Symbol::RightBrace) {
+                end = tokens[t].pos;
                 t += 1;
                 break;
             }
@@ -186,6 +192,8 @@ impl<'f> Func<'f> {
             t,
             Func {
                 name,
+                pos,
+                end,
                 args,
                 ret: ty.unwrap_or(Ty::Void),
                 variadic: false, // FIXME
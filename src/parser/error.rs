@@ -45,14 +45,18 @@ impl<'e> Error<'e> {
         }
     }
 
-    pub(super) fn is_wrong_token(&self) -> bool {
-        if let ErrorKind::WrongToken { .. } = self.kind {
-            true
-        } else {
-            false
+    pub fn pos(&self) -> Option<Position> {
+        match &self.kind {
+            ErrorKind::MissingToken { after, .. } => *after,
+            ErrorKind::WrongToken { token, .. } => Some(token.pos),
+            ErrorKind::Multiple(errors) => errors.iter().filter_map(|error| error.pos()).max(),
         }
     }
 
+    pub(super) fn is_wrong_token(&self) -> bool {
+        matches!(self.kind, ErrorKind::WrongToken { .. })
+    }
+
     pub(super) fn max_after(&mut self, after: Option<Position>) {
         if let ErrorKind::MissingToken { after: after_, .. } = &mut self.kind {
             match (after_, after) {
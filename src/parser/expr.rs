@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -5,7 +6,9 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 use crate::lexer;
+use crate::lexer::FormatSpec;
 use crate::lexer::Ident;
+use crate::lexer::Position;
 use crate::lexer::Symbol;
 use crate::lexer::Token;
 use crate::lexer::TokenTy;
@@ -30,11 +33,12 @@ pub enum Expr<'e> {
 pub enum Literal<'l> {
     Int {
         name: Ident<'l>,
-        int: i32,
+        id: usize,
+        int: i64,
     },
     String {
         name: Ident<'l>,
-        string: &'l str,
+        string: Cow<'l, str>,
     },
     RefDynString {
         name: Ident<'l>,
@@ -48,13 +52,14 @@ pub enum Literal<'l> {
 
 #[derive(Debug)]
 pub enum DynStringSeg<'s> {
-    String(&'s str),
-    Expr(Expr<'s>),
+    String(Cow<'s, str>),
+    Expr(Expr<'s>, Option<FormatSpec>),
 }
 
 #[derive(Debug)]
 pub struct Func<'f> {
     pub name: Ident<'f>,
+    pub pos: Position,
     pub args: Args<'f>,
 }
 
@@ -68,8 +73,9 @@ impl<'e> Expr<'e> {
     pub fn as_ref(&'e self) -> Expr<'e> {
         match self {
             Expr::Literal(lit) => Expr::Literal(lit.as_ref()),
-            Expr::Func(Func { name, args }) => Expr::Func(Func {
+            Expr::Func(Func { name, pos, args }) => Expr::Func(Func {
                 name: name.as_ref(),
+                pos: *pos,
                 args: args.as_ref(),
             }),
             Expr::Var(var) => Expr::Var(var.as_ref()),
@@ -84,7 +90,7 @@ impl<'e> Expr<'e> {
         handled
     }
 
-    pub(super) fn parse(tokens: &'e [Token<'e>]) -> Result<(usize, Expr<'e>)> {
+    pub(super) fn parse(tokens: &'e [Token<'e>]) -> Result<'e, (usize, Expr<'e>)> {
         if tokens.is_empty() {
             return Err(Error::missing_token(Self::handled(), None));
         }
@@ -94,16 +100,15 @@ impl<'e> Expr<'e> {
         if let TokenVariant::Literal(lit) = &tokens[0].token {
             match lit {
                 lexer::Literal::Int(int) => {
+                    let id = LITERALS.fetch_add(1, Ordering::SeqCst);
                     return Ok((
                         1,
                         Expr::Literal(Literal::Int {
-                            name: Ident::Owned(format!(
-                                "lit{}",
-                                LITERALS.fetch_add(1, Ordering::SeqCst)
-                            )),
+                            name: Ident::Owned(format!("lit{}", id)),
+                            id,
                             int: *int,
                         }),
-                    ))
+                    ));
                 }
                 lexer::Literal::String(string) => {
                     return Ok((
@@ -113,7 +118,7 @@ impl<'e> Expr<'e> {
                                 "lit{}",
                                 LITERALS.fetch_add(1, Ordering::SeqCst)
                             )),
-                            string,
+                            string: unescape(string),
                         }),
                     ))
                 }
@@ -122,12 +127,18 @@ impl<'e> Expr<'e> {
                     for seg in segs_ {
                         match seg {
                             lexer::DynStringSeg::String(string) => {
-                                segs.push(DynStringSeg::String(string));
+                                segs.push(DynStringSeg::String(unescape(string)));
                             }
-                            lexer::DynStringSeg::Insert(tokens) => {
+                            lexer::DynStringSeg::Insert(tokens, spec, _) => {
                                 let (i, expr) = Expr::parse(tokens)?;
-                                assert_eq!(i, tokens.len()); // FIXME
-                                segs.push(DynStringSeg::Expr(expr));
+                                if i < tokens.len() {
+                                    return Err(Error::wrong_token(
+                                        &tokens[i],
+                                        vec![TokenTy::Symbol(Symbol::RightBrace)],
+                                    ));
+                                }
+
+                                segs.push(DynStringSeg::Expr(expr, *spec));
                             }
                         }
                     }
@@ -171,13 +182,14 @@ impl<'e> Expr<'e> {
 impl<'l> Literal<'l> {
     pub fn as_ref(&'l self) -> Literal<'l> {
         match self {
-            Literal::Int { name, int } => Literal::Int {
+            Literal::Int { name, id, int } => Literal::Int {
                 name: name.as_ref(),
+                id: *id,
                 int: *int,
             },
             Literal::String { name, string } => Literal::String {
                 name: name.as_ref(),
-                string,
+                string: Cow::Borrowed(string),
             },
             Literal::RefDynString { name, segs } => Literal::RefDynString {
                 name: name.as_ref(),
@@ -189,15 +201,34 @@ impl<'l> Literal<'l> {
             },
         }
     }
+}
 
-    pub fn name(&'l self) -> Ident<'l> {
-        match self {
-            Literal::Int { name, .. } => name.as_ref(),
-            Literal::String { name, .. } => name.as_ref(),
-            Literal::RefDynString { name, .. } => name.as_ref(),
-            Literal::OwnedDynString { name, .. } => name.as_ref(),
+// the lexer checked the escapes, and keeps them for the formatter
+fn unescape(string: &str) -> Cow<'_, str> {
+    if !string.contains('\\') {
+        return Cow::Borrowed(string);
+    }
+
+    let mut unescaped = String::with_capacity(string.len());
+
+    let mut chars = string.chars();
+    while let Some(chr) = chars.next() {
+        if chr != '\\' {
+            unescaped.push(chr);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('0') => unescaped.push('\0'),
+            Some(chr) => unescaped.push(chr),
+            None => unescaped.push('\\'),
         }
     }
+
+    Cow::Owned(unescaped)
 }
 
 impl<'f> Func<'f> {
@@ -205,7 +236,7 @@ impl<'f> Func<'f> {
         vec![TokenTy::Ident]
     }
 
-    fn parse(tokens: &'f [Token<'f>]) -> Result<(usize, Func<'f>)> {
+    fn parse(tokens: &'f [Token<'f>]) -> Result<'f, (usize, Func<'f>)> {
         let name = try_get_ident(tokens, 0)?.as_ref();
 
         let mut t = 1;
@@ -248,6 +279,7 @@ impl<'f> Func<'f> {
             t,
             Func {
                 name,
+                pos: tokens[0].pos,
                 args: Args::Owned(args),
             },
         ))
@@ -259,7 +291,7 @@ impl<'a> Args<'a> {
         self.inner().len()
     }
 
-    pub fn inner(&self) -> &[Expr] {
+    pub fn inner(&self) -> &[Expr<'_>] {
         match self {
             Args::Ref(args) => args,
             Args::Owned(args) => args,
@@ -288,7 +320,7 @@ impl<'e> Display for Expr<'e> {
 impl<'l> Display for Literal<'l> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match self {
-            Literal::Int { name, int } => {
+            Literal::Int { name, int, .. } => {
                 write!(fmt, "lit(name={}, value=int({}))", name.inner(), int)
             }
             Literal::String { name, string } => {
@@ -320,7 +352,8 @@ impl<'s> Display for DynStringSeg<'s> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match self {
             DynStringSeg::String(string) => write!(fmt, "string({:?}", string),
-            DynStringSeg::Expr(expr) => write!(fmt, "{}", expr),
+            DynStringSeg::Expr(expr, None) => write!(fmt, "{}", expr),
+            DynStringSeg::Expr(expr, Some(spec)) => write!(fmt, "{}:{}", expr, spec),
         }
     }
 }
@@ -336,3 +369,31 @@ impl<'f> Display for Func<'f> {
         write!(fmt, "])")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::lex;
+
+    fn parse(input: &str) -> Vec<String> {
+        let tokens = lex(input).unwrap();
+        match Expr::parse(&tokens).unwrap().1 {
+            Expr::Literal(Literal::String { string, .. }) => vec![string.into_owned()],
+            Expr::Literal(Literal::OwnedDynString { segs, .. }) => segs
+                .into_iter()
+                .filter_map(|seg| match seg {
+                    DynStringSeg::String(string) => Some(string.into_owned()),
+                    DynStringSeg::Expr(..) => None,
+                })
+                .collect(),
+            expr => panic!("expected a string, got {}", expr),
+        }
+    }
+
+    #[test]
+    fn decodes_escapes() {
+        assert_eq!(parse(r#""a\n\t\"\\b""#), vec!["a\n\t\"\\b"]);
+        assert_eq!(parse(r"`\`\$${x}\0%`"), vec!["`$", "\0%"]);
+        assert_eq!(parse(r#""50%""#), vec!["50%"]);
+    }
+}
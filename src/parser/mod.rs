@@ -17,7 +17,7 @@ mod module;
 pub use error::*;
 pub use module::Module;
 
-pub fn parse<'t>(tokens: &'t [Token<'t>]) -> Result<Module> {
+pub fn parse<'t>(tokens: &'t [Token<'t>]) -> Result<'t, Module<'t>> {
     let mut module = Module::new(Ident::Owned("main".into())); // FIXME
     let t = module.parse(tokens)?;
 
@@ -47,7 +47,7 @@ fn try_get_ty<'t>(tokens: &'t [Token<'t>], at: usize) -> Result<'t, Ty> {
     }
 }
 
-fn try_get_ident<'t>(tokens: &'t [Token<'t>], at: usize) -> Result<&'t Ident<'t>> {
+fn try_get_ident<'t>(tokens: &'t [Token<'t>], at: usize) -> Result<'t, &'t Ident<'t>> {
     match tokens.get(at).map(|token| (token, &token.token)) {
         Some((_, TokenVariant::Ident(ident))) => Ok(ident),
         Some((token, _)) => Err(Error::wrong_token(token, vec![TokenTy::Ident])),
@@ -27,7 +27,7 @@ impl<'m> Module<'m> {
 
     pub(super) fn handled() -> Vec<TokenTy> {
         let mut handled = Decl::handled();
-        handled.push(TokenTy::EOF);
+        handled.push(TokenTy::Eof);
 
         handled
     }
@@ -36,7 +36,7 @@ impl<'m> Module<'m> {
         let mut t = 0;
         loop {
             if t >= tokens.len() {
-                return Err(Error::missing_token(vec![TokenTy::EOF], None));
+                return Err(Error::missing_token(vec![TokenTy::Eof], None));
             }
 
             if tokens[t].is_eof() {
@@ -4,9 +4,11 @@ use std::fmt::Formatter;
 
 use crate::lexer::Ident;
 use crate::lexer::Keyword;
+use crate::lexer::Position;
 use crate::lexer::Symbol;
 use crate::lexer::Token;
 use crate::lexer::TokenTy;
+use crate::lexer::Ty;
 
 use super::error::*;
 use super::expr::Expr;
@@ -14,6 +16,7 @@ use super::split;
 use super::try_eq_keyword;
 use super::try_eq_symbol;
 use super::try_get_ident;
+use super::try_get_ty;
 
 #[derive(Debug)]
 pub enum Stmt<'s> {
@@ -25,11 +28,13 @@ pub enum Stmt<'s> {
 #[derive(Debug)]
 pub struct Let<'l> {
     pub name: Ident<'l>,
+    pub pos: Position,
+    pub ty: Option<Ty>,
     pub value: Expr<'l>,
 }
 
 #[derive(Debug)]
-pub struct Return<'r>(pub Expr<'r>);
+pub struct Return<'r>(pub Expr<'r>, pub Position);
 
 impl<'s> Stmt<'s> {
     pub(super) fn handled() -> Vec<TokenTy> {
@@ -40,7 +45,7 @@ impl<'s> Stmt<'s> {
         handled
     }
 
-    pub(super) fn parse(tokens: &'s [Token<'s>]) -> Result<(usize, Stmt<'s>)> {
+    pub(super) fn parse(tokens: &'s [Token<'s>]) -> Result<'s, (usize, Stmt<'s>)> {
         if tokens.is_empty() {
             return Err(Error::missing_token(Self::handled(), None));
         }
@@ -60,7 +65,7 @@ impl<'s> Stmt<'s> {
             }
             Err(mut err) => {
                 error = error.concat({
-                    err.max_after(tokens.get(0).map(|token| token.pos));
+                    err.max_after(tokens.first().map(|token| token.pos));
                     err
                 })
             }
@@ -79,7 +84,7 @@ impl<'s> Stmt<'s> {
             }
             Err(mut err) => {
                 error = error.concat({
-                    err.max_after(tokens.get(0).map(|token| token.pos));
+                    err.max_after(tokens.first().map(|token| token.pos));
                     err
                 })
             }
@@ -98,7 +103,7 @@ impl<'s> Stmt<'s> {
             }
             Err(mut err) => {
                 error = error.concat({
-                    err.max_after(tokens.get(0).map(|token| token.pos));
+                    err.max_after(tokens.first().map(|token| token.pos));
                     err
                 })
             }
@@ -112,6 +117,8 @@ impl<'l> Let<'l> {
     pub fn as_ref(&'l self) -> Let<'l> {
         Let {
             name: self.name.as_ref(),
+            pos: self.pos,
+            ty: self.ty,
             value: self.value.as_ref(),
         }
     }
@@ -120,7 +127,7 @@ impl<'l> Let<'l> {
         vec![TokenTy::Keyword(Keyword::Let)]
     }
 
-    fn parse(tokens: &'l [Token<'l>]) -> Result<(usize, Let<'l>)> {
+    fn parse(tokens: &'l [Token<'l>]) -> Result<'l, (usize, Let<'l>)> {
         try_eq_keyword(tokens, 0, Keyword::Let)?;
 
         let mut t = 1;
@@ -130,8 +137,27 @@ impl<'l> Let<'l> {
                 err
             })?
             .as_ref();
+        let pos = tokens[t].pos;
 
         t += 1;
+        let ty = match try_eq_symbol(tokens, t, Symbol::Colon) {
+            Ok(()) => {
+                t += 1;
+                let ty = Some(try_get_ty(tokens, t).map_err(|mut err| {
+                    err.max_after(tokens.get(t - 1).map(|token| token.pos));
+                    err
+                })?);
+
+                t += 1;
+                ty
+            }
+            Err(ref err) if err.is_wrong_token() => None,
+            Err(mut err) => {
+                err.max_after(tokens.get(t - 1).map(|token| token.pos));
+                return Err(err);
+            }
+        };
+
         try_eq_symbol(tokens, t, Symbol::Equal).map_err(|mut err| {
             err.max_after(tokens.get(t - 1).map(|token| token.pos));
             err
@@ -143,28 +169,36 @@ impl<'l> Let<'l> {
             err
         })?;
 
-        Ok((t + t_, Let { name, value }))
+        Ok((
+            t + t_,
+            Let {
+                name,
+                pos,
+                ty,
+                value,
+            },
+        ))
     }
 }
 
 impl<'r> Return<'r> {
     pub fn as_ref(&'r self) -> Return<'r> {
-        Return(self.0.as_ref())
+        Return(self.0.as_ref(), self.1)
     }
 
     fn handled() -> Vec<TokenTy> {
         vec![TokenTy::Keyword(Keyword::Return)]
     }
 
-    fn parse(tokens: &'r [Token<'r>]) -> Result<(usize, Return<'r>)> {
+    fn parse(tokens: &'r [Token<'r>]) -> Result<'r, (usize, Return<'r>)> {
         try_eq_keyword(tokens, 0, Keyword::Return)?;
 
         let (t, expr) = Expr::parse(split(tokens, 1)).map_err(|mut err| {
-            err.max_after(tokens.get(0).map(|token| token.pos));
+            err.max_after(tokens.first().map(|token| token.pos));
             err
         })?;
 
-        Ok((t + 1, Return(expr)))
+        Ok((t + 1, Return(expr, tokens[0].pos)))
     }
 }
 
@@ -181,7 +215,14 @@ impl<'s> Display for Stmt<'s> {
 
 impl<'l> Display for Let<'l> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        write!(fmt, "let(name={}, value={})", self.name, self.value)
+        match self.ty {
+            Some(ty) => write!(
+                fmt,
+                "let(name={}, ty={}, value={})",
+                self.name, ty, self.value
+            ),
+            None => write!(fmt, "let(name={}, value={})", self.name, self.value),
+        }
     }
 }
 
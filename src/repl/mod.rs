@@ -0,0 +1,325 @@
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::iter;
+use std::mem;
+
+use fnv::FnvHashMap;
+
+use crate::ast::Stmt;
+use crate::ast::Tree;
+use crate::dependencies::Graph;
+use crate::interpreter::Interpreter;
+use crate::interpreter::Value;
+use crate::interpreter::Vars;
+use crate::lexer::lex;
+use crate::lexer::lex_recover;
+use crate::lexer::Ident;
+use crate::lexer::Keyword;
+use crate::lexer::Lexer;
+use crate::lexer::Position;
+use crate::lexer::Symbol;
+use crate::lexer::Token;
+use crate::lexer::TokenVariant;
+use crate::lexer::Ty;
+use crate::parser::decl;
+use crate::parser::decl::Arg;
+use crate::parser::parse;
+use crate::parser::Module;
+
+const REPL: &str = "<repl>";
+
+#[derive(Debug)]
+struct Repl {
+    // the sources of the accepted declarations, reparsed with every input
+    decls: Vec<String>,
+    vars: FnvHashMap<Ident<'static>, Value>,
+}
+
+pub fn run() -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut repl = Repl {
+        decls: vec![],
+        vars: FnvHashMap::default(),
+    };
+
+    let mut input = String::new();
+    loop {
+        print!("{}", if input.is_empty() { ">> " } else { ".. " });
+        io::stdout().flush()?;
+
+        if stdin.lock().read_line(&mut input)? == 0 {
+            println!();
+            return Ok(());
+        }
+
+        if input.trim().is_empty() {
+            input.clear();
+        } else if complete(&input) {
+            repl.eval(mem::take(&mut input));
+        }
+    }
+}
+
+impl Repl {
+    fn eval(&mut self, input: String) {
+        let decl = match Lexer::new(&input).peek() {
+            Some(Ok(token)) => token.eq_keyword(Keyword::Func),
+            _ => false,
+        };
+
+        let mut tokens = vec![];
+        for source in self.decls.iter().chain(iter::once(&input)) {
+            match lex(source) {
+                Ok(tokens_) => tokens.push(tokens_),
+                Err(err) => {
+                    for err in err.errors() {
+                        let pos = err.pos().unwrap(); // `errors` flattens `Multiple`
+                        eprintln!("{}:{}: {}", pos.line + 1, pos.col + 1, err);
+                    }
+
+                    return;
+                }
+            }
+        }
+
+        if !decl {
+            let input = tokens.pop().unwrap(); // the input is always last
+            tokens.push(wrap(input));
+        }
+
+        // a later declaration replaces an earlier one with the same name
+        let mut funcs: Vec<decl::Func> = vec![];
+        for tokens in &tokens {
+            match parse(tokens) {
+                Ok(module) => {
+                    for func in module.funcs {
+                        funcs.retain(|func_| func_.name != func.name);
+                        funcs.push(func);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return;
+                }
+            }
+        }
+
+        let stmts = if decl {
+            vec![]
+        } else {
+            funcs.pop().unwrap().stmts // the wrapped input is always last
+        };
+
+        funcs.push(decl::Func {
+            name: Ident::Ref(REPL),
+            pos: Position::default(),
+            end: Position::default(),
+            args: self
+                .vars
+                .iter()
+                .map(|(name, value)| Arg {
+                    name: name.clone(),
+                    pos: Position::default(),
+                    ty: value.ty(),
+                })
+                .collect(),
+            ret: Ty::Void,
+            variadic: false,
+            stmts,
+        });
+
+        let module = Module {
+            name: Ident::Ref("repl"),
+            funcs,
+        };
+
+        if exec(&module, &mut self.vars) && decl {
+            self.decls.push(input);
+        }
+    }
+}
+
+fn exec(module: &Module, vars_: &mut FnvHashMap<Ident<'static>, Value>) -> bool {
+    let mut ast = Tree::build(&[module]);
+    ast.declare_builtins();
+    ast.infer();
+
+    if let Err(err) = Graph::build(&ast).verify() {
+        for err in err.errors() {
+            eprintln!("{}", err);
+        }
+
+        return false;
+    }
+
+    if let Err(err) = ast.verify() {
+        for err in err.errors() {
+            eprintln!("{}", err);
+        }
+
+        return false;
+    }
+
+    let interpreter = Interpreter::new(&ast);
+    let mut vars: Vars = vars_.clone();
+
+    let mut next = ast.funcs[&Ident::Ref(REPL)].start;
+    while let Some(next_) = next {
+        let stmt = ast.stmts[next_].as_ref().unwrap(); // FIXME
+        let value = match stmt {
+            Stmt::Let { let_, .. } => match interpreter.eval(&let_.value, &vars) {
+                Ok(value) => {
+                    let name = let_.name.inner();
+                    vars_.insert(Ident::Owned(name.into()), value.clone());
+                    vars.insert(let_.name.as_ref(), value);
+
+                    Ok(Value::Void)
+                }
+                Err(err) => Err(err),
+            },
+            Stmt::Return { ret, .. } => interpreter.eval(&ret.0, &vars),
+            Stmt::Expr { expr, .. } => interpreter.eval(expr, &vars),
+        };
+
+        match value {
+            Ok(Value::Void) => (),
+            Ok(Value::Str(string)) => println!("{:?}: {}", string, Ty::Str.as_str()),
+            Ok(value) => println!("{}: {}", value, value.ty().as_str()),
+            Err(err) => {
+                eprintln!("{}", err);
+                break;
+            }
+        }
+
+        next = stmt.next();
+    }
+
+    true
+}
+
+fn complete(input: &str) -> bool {
+    let mut depth = 0;
+    for token in lex_recover(input) {
+        match token.token {
+            TokenVariant::Symbol(Symbol::LeftBrace) => depth += 1,
+            TokenVariant::Symbol(Symbol::RightBrace) => depth -= 1,
+            _ => (),
+        }
+    }
+
+    depth <= 0
+}
+
+fn wrap(mut tokens: Vec<Token>) -> Vec<Token> {
+    let eof = tokens.pop().unwrap(); // lex always ends with EOF
+    let terminated = match tokens.last() {
+        Some(token) => token.eq_symbol(Symbol::SemiColon) || token.eq_symbol(Symbol::RightBrace),
+        None => true,
+    };
+
+    let pos = Position::default();
+    let mut wrapped = vec![
+        Token {
+            token: TokenVariant::Keyword(Keyword::Func),
+            pos,
+        },
+        Token {
+            token: TokenVariant::Ident(Ident::Ref(REPL)),
+            pos,
+        },
+        Token {
+            token: TokenVariant::Symbol(Symbol::LeftParen),
+            pos,
+        },
+        Token {
+            token: TokenVariant::Symbol(Symbol::RightParen),
+            pos,
+        },
+        Token {
+            token: TokenVariant::Symbol(Symbol::LeftBrace),
+            pos,
+        },
+    ];
+
+    wrapped.append(&mut tokens);
+    if !terminated {
+        wrapped.push(Token {
+            token: TokenVariant::Symbol(Symbol::SemiColon),
+            pos: eof.pos,
+        });
+    }
+
+    wrapped.push(Token {
+        token: TokenVariant::Symbol(Symbol::RightBrace),
+        pos: eof.pos,
+    });
+    wrapped.push(eof);
+
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl(inputs: &[&str]) -> Repl {
+        let mut repl = Repl {
+            decls: vec![],
+            vars: FnvHashMap::default(),
+        };
+
+        for input in inputs {
+            repl.eval(input.to_string());
+        }
+
+        repl
+    }
+
+    fn var(repl: &Repl, name: &'static str) -> Option<Value> {
+        repl.vars.get(&Ident::Ref(name)).cloned()
+    }
+
+    #[test]
+    fn carries_vars_between_inputs() {
+        let repl = repl(&["let x = 1;", r#"let y = concat(to_str(x), "!");"#]);
+
+        assert_eq!(var(&repl, "x"), Some(Value::I32(1)));
+        assert_eq!(var(&repl, "y"), Some(Value::Str("1!".into())));
+    }
+
+    #[test]
+    fn rebinds_vars_to_another_ty() {
+        let repl = repl(&["let x = 1;", r#"let x = "kitty";"#, "let y = len(x);"]);
+
+        assert_eq!(var(&repl, "x"), Some(Value::Str("kitty".into())));
+        assert_eq!(var(&repl, "y"), Some(Value::I32(5)));
+    }
+
+    #[test]
+    fn redefines_funcs() {
+        let repl = repl(&[
+            "func answer(): i32 { return 41; }",
+            "let before = answer();",
+            "func answer(): i32 { return 42; }",
+            "let after = answer();",
+        ]);
+
+        assert_eq!(var(&repl, "before"), Some(Value::I32(41)));
+        assert_eq!(var(&repl, "after"), Some(Value::I32(42)));
+    }
+
+    #[test]
+    fn forgets_inputs_that_fail() {
+        let repl = repl(&[
+            "func answer(): i32 { return 42; }",
+            "func answer(): i32 { }",
+            "let broken = missing();",
+            "let answer = answer();",
+        ]);
+
+        assert_eq!(repl.decls.len(), 1);
+        assert_eq!(var(&repl, "broken"), None);
+        assert_eq!(var(&repl, "answer"), Some(Value::I32(42)));
+    }
+}